@@ -0,0 +1,50 @@
+use crate::{HomingError, HomingModel, Image, Vec2, View};
+
+/// the average landmark vector model by Lambrinos et al.
+/// the average of the unit vectors pointing to the landmarks is compared instead of the segments,
+/// the homing vector is the difference between the current and the snapshot average
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Default)]
+pub struct AlvModel;
+
+impl AlvModel {
+    /// returns the average of the unit vectors pointing to the bisectors of the landmark segments
+    /// white segments are the background between the landmarks and are left out
+    pub fn landmark_vector(image: &Image) -> Vec2<f32> {
+        let mut sum = Vec2::<f32>::new(0.0, 0.0);
+        let mut count = 0;
+        for segment in image.segments.iter().filter(|s| s.color.is_landmark()) {
+            sum += Vec2::<f32>::new(segment.bisector.cos(), segment.bisector.sin());
+            count += 1;
+        }
+        // without landmarks the average stays zero
+        if count == 0 {
+            return sum;
+        }
+        (1.0 / count as f32) * sum
+    }
+}
+
+impl HomingModel for AlvModel {
+    /// the homing vector is not normalized, it gets shorter the closer the bee is to its home
+    fn home(&self, snapshot: &Image, view: &View) -> Result<Vec2<f32>, HomingError> {
+        Ok(AlvModel::landmark_vector(&view.current) - AlvModel::landmark_vector(snapshot))
+    }
+}
+
+#[test]
+fn alv_test() {
+    use crate::{Scene, VectorField};
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    let bee = scene.bee(&world).unwrap().with_model(Box::new(AlvModel));
+    let error = VectorField::generate(bee, &world)
+        .avg_angular_error
+        .unwrap();
+
+    println!("{}", error.to_degrees());
+
+    // the field leads home across the whole arena
+    assert!(error < 20f32.to_radians());
+}
@@ -0,0 +1,144 @@
+use crate::{
+    Compass, HomingError, HomingModel, Image, Retina, Snapshot, SnapshotModel, SnapshotSelection,
+    Vec2, View, World,
+};
+
+/// bee struct to hold information about the snapshots and its position
+pub struct Bee {
+    /// snapshots of all obstacles, the first one is taken at home
+    pub snapshots: Vec<Snapshot>,
+    /// which snapshot drives the homing vector at the current position
+    pub selection: SnapshotSelection,
+    /// position of the bee
+    pub position: Vec2<f32>,
+    /// position where the first snapshot was taken
+    pub home: Vec2<f32>,
+    /// the model that turns the snapshot and the current image into a homing vector
+    pub model: Box<dyn HomingModel>,
+    /// the compass that orients the current image, perfect by default
+    pub compass: Compass,
+    /// the retina the images are seen through, None sees the exact image circle
+    pub retina: Option<Retina>,
+}
+
+impl Bee {
+    /// creates a new bee at its home position
+    /// the snapshot is taken from the home position
+    pub fn new(world: &World, home_position: Vec2<f32>) -> Result<Bee, HomingError> {
+        let image = Image::new(home_position, &world.obstacles)?;
+        Ok(Bee {
+            snapshots: vec![Snapshot::new(home_position, image, home_position)],
+            selection: SnapshotSelection::Home,
+            position: home_position,
+            home: home_position,
+            model: Box::new(SnapshotModel::default()),
+            compass: Compass::default(),
+            retina: None,
+        })
+    }
+    /// replaces the homing model of the bee
+    pub fn with_model(mut self, model: Box<dyn HomingModel>) -> Bee {
+        self.model = model;
+        self
+    }
+    /// replaces the compass of the bee
+    pub fn with_compass(mut self, compass: Compass) -> Bee {
+        self.compass = compass;
+        self
+    }
+    /// lets the bee see through the given retina
+    /// the snapshots are resampled with the retina as well
+    pub fn with_retina(mut self, retina: Retina) -> Bee {
+        for snapshot in &mut self.snapshots {
+            snapshot.image = retina.resample(&snapshot.image);
+        }
+        self.retina = Some(retina);
+        self
+    }
+    /// lets the bee take another snapshot at the given position
+    pub fn with_snapshot(mut self, world: &World, position: Vec2<f32>) -> Result<Bee, HomingError> {
+        let image = Image::new(position, &world.obstacles)?;
+        let image = match self.retina {
+            Some(retina) => retina.resample(&image),
+            None => image,
+        };
+        self.snapshots
+            .push(Snapshot::new(position, image, self.home));
+        Ok(self)
+    }
+    /// replaces the policy for choosing a snapshot
+    pub fn with_selection(mut self, selection: SnapshotSelection) -> Bee {
+        self.selection = selection;
+        self
+    }
+    /// chooses the snapshot for the current position with the selection policy of the bee
+    /// every snapshot is compared with the current image as the compass aligns it with that snapshot
+    pub fn snapshot(&self, world: &World) -> Result<&Snapshot, HomingError> {
+        let image = Image::new(self.position, &world.obstacles)?;
+        self.selection
+            .select(&self.snapshots, |snapshot| {
+                self.perceive(self.position, &snapshot.image, image.clone())
+            })
+            .ok_or(HomingError::NoSnapshot)
+    }
+    /// calculates the homing vector for the current position of the bee
+    /// away from home the vector towards the selected snapshot is guided home by its home vector
+    pub fn home(&self, world: &World) -> Result<Vec2<f32>, HomingError> {
+        // pick the snapshot and look around aligned with it
+        let snapshot = self.snapshot(world)?;
+        let view = View::new(self, world, snapshot)?;
+        // let the model compare the view with the snapshot
+        let vec = self.model.home(&snapshot.image, &view)?;
+        Ok(snapshot.guide(vec))
+    }
+    /// calculates the vector towards the position of the selected snapshot
+    /// unlike Bee::home the bee does not head on towards home from there
+    pub fn approach(&self, world: &World) -> Result<Vec2<f32>, HomingError> {
+        let snapshot = self.snapshot(world)?;
+        let view = View::new(self, world, snapshot)?;
+        self.model.home(&snapshot.image, &view)
+    }
+    /// counts the snapshot segments the model leaves unmatched at the current position of the bee
+    pub fn unmatched(&self, world: &World) -> Result<usize, HomingError> {
+        let snapshot = self.snapshot(world)?;
+        let retinal_image = self.image_at(self.position, world, snapshot)?;
+        self.model.unmatched(&snapshot.image, &retinal_image)
+    }
+    /// takes the image at the given position, orients it with the compass
+    /// and samples it with the retina
+    /// the visual compass aligns the image with the given snapshot
+    pub fn image_at(
+        &self,
+        position: Vec2<f32>,
+        world: &World,
+        snapshot: &Snapshot,
+    ) -> Result<Image, HomingError> {
+        let image = Image::new(position, &world.obstacles)?;
+        Ok(self.perceive(position, &snapshot.image, image))
+    }
+    /// orients the image taken at the given position against the snapshot and samples it with the retina
+    fn perceive(&self, position: Vec2<f32>, snapshot: &Image, image: Image) -> Image {
+        let image = self.compass.orient(position, snapshot, image);
+        match self.retina {
+            Some(retina) => retina.resample(&image),
+            None => image,
+        }
+    }
+}
+
+/// this test will always pass if the program doesnt crash
+#[test]
+fn help() {
+    use crate::Scene;
+
+    // constructing the world
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    let mut bee = scene.bee(&world).unwrap();
+
+    bee.position = Vec2::<f32>::new(5.0, -5.0);
+
+    let out = bee.home(&world).unwrap();
+    println!("{:?}", out);
+}
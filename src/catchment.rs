@@ -0,0 +1,91 @@
+use crate::{Bee, Grid, Outcome, Simulation, World};
+
+/// the start positions from which a bee finds its way home
+#[derive(Clone, PartialEq, Debug)]
+pub struct CatchmentArea {
+    /// the grid the start positions were sampled on
+    pub grid: Grid,
+    /// number of steps the bee needed to get home from every start position, indexed by Grid::index
+    /// start positions from which the bee does not get home hold None
+    pub steps: Vec<Vec<Option<usize>>>,
+    /// area covered by the grid positions from which the bee gets home
+    pub area: f32,
+}
+
+impl CatchmentArea {
+    /// simulates a trajectory from every position on the grid of the world
+    pub fn compute(mut bee: Bee, world: &World, simulation: &Simulation) -> CatchmentArea {
+        let grid = world.grid.clone();
+        let mut steps = vec![vec![None; grid.rows()]; grid.columns()];
+
+        for position in grid.positions() {
+            let index = grid.index(position);
+            let trajectory = simulation.run(&mut bee, world, position);
+            if trajectory.outcome == Outcome::Arrived {
+                steps[index[0]][index[1]] = Some(trajectory.path.len() - 1);
+            }
+        }
+
+        // every grid position stands for a square with the side length of the spacing
+        let reached = steps.iter().flatten().filter(|s| s.is_some()).count();
+        let area = reached as f32 * grid.spacing * grid.spacing;
+
+        CatchmentArea { grid, steps, area }
+    }
+    /// writes the catchment area as csv into the file at the given path
+    /// every line holds the start position and the number of steps: x,y,steps
+    /// the steps are left empty where the bee does not get home
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(file, "x,y,steps")?;
+        for position in self.grid.positions() {
+            let index = self.grid.index(position);
+            match self.steps[index[0]][index[1]] {
+                Some(steps) => writeln!(file, "{},{},{}", position[0], position[1], steps)?,
+                None => writeln!(file, "{},{},", position[0], position[1])?,
+            }
+        }
+        file.flush()
+    }
+}
+
+#[test]
+fn catchment_test() {
+    use crate::{Scene, SnapshotSelection, Vec2};
+
+    let mut scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    scene.grid.width = [-2.0, 3.0];
+    scene.grid.height = [-2.0, 3.0];
+    let world = scene.world();
+    let bee = scene.bee(&world).unwrap();
+
+    let catchment = CatchmentArea::compute(bee, &world, &Simulation::default());
+
+    println!("{:?}", catchment);
+
+    // the bee gets home from everywhere close to it, right away from home itself
+    assert_eq!(catchment.area, 25.0);
+    let home = catchment.grid.index(Vec2::<f32>::new(0.0, 0.0));
+    assert_eq!(catchment.steps[home[0]][home[1]], Some(0));
+
+    // further snapshots, one of them behind a landmark, enlarge the catchment area
+    scene.grid.height = [-7.0, 1.0];
+    let world = scene.world();
+    let bee = scene.bee(&world).unwrap();
+    let home_only = CatchmentArea::compute(bee, &world, &Simulation::default());
+
+    let bee = [(0.0, -5.5), (4.0, -5.0), (-4.0, 4.0)]
+        .into_iter()
+        .try_fold(scene.bee(&world).unwrap(), |bee, (x, y)| {
+            bee.with_snapshot(&world, Vec2::<f32>::new(x, y))
+        })
+        .unwrap()
+        .with_selection("lowest-difference".parse::<SnapshotSelection>().unwrap());
+    let snapshots = CatchmentArea::compute(bee, &world, &Simulation::default());
+
+    println!("{} {}", home_only.area, snapshots.area);
+
+    assert!(snapshots.area > home_only.area);
+}
@@ -0,0 +1,105 @@
+use std::f32::consts::PI;
+
+use rand::{rngs::StdRng, SeedableRng};
+use rand_distr::{Distribution, Normal};
+
+use crate::{Image, Vec2};
+
+/// number of rotations the visual compass tries when aligning two images
+const ALIGNMENT_STEPS: usize = 360;
+
+/// the compass of a bee that orients the current image before it is compared with the snapshot
+/// a perfect compass shares the frame of the snapshot, a faulty one rotates the current image
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Compass {
+    /// fixed error of the heading, radians
+    pub offset: f32,
+    /// standard deviation of the random error of the heading, radians
+    pub noise: f32,
+    /// seed of the random error, the error at a position is the same on every run
+    pub seed: u64,
+    /// estimate the rotation by aligning the current image with the snapshot before matching
+    /// only rotations up to the largest heading error are tried
+    pub visual: bool,
+}
+
+impl Compass {
+    /// rotates the current image at the given position by the heading error
+    /// with the visual compass the rotation is estimated and undone afterwards
+    pub fn orient(&self, position: Vec2<f32>, snapshot: &Image, current: Image) -> Image {
+        let rotated = current.rotated(self.heading_error(position));
+        if self.visual {
+            let estimate = Compass::alignment(snapshot, &rotated, self.max_error());
+            rotated.rotated(-estimate)
+        } else {
+            rotated
+        }
+    }
+    /// error of the heading at the given position, radians
+    pub fn heading_error(&self, position: Vec2<f32>) -> f32 {
+        if self.noise <= 0.0 {
+            return self.offset;
+        }
+        // derive the random generator from the position so that every position has its own error
+        let position_bits = (position[0].to_bits() as u64) << 32 | position[1].to_bits() as u64;
+        let mut rng = StdRng::seed_from_u64(self.seed ^ position_bits);
+        match Normal::new(0.0, self.noise) {
+            Ok(normal) => self.offset + normal.sample(&mut rng),
+            Err(_) => self.offset,
+        }
+    }
+    /// largest heading error the compass makes, the fixed error and three standard deviations, radians
+    pub fn max_error(&self) -> f32 {
+        self.offset.abs() + 3.0 * self.noise
+    }
+    /// estimates the rotation of the current image relative to the snapshot, radians
+    /// the rotation up to the given one in either direction
+    /// with the smallest squared difference in brightness is chosen
+    /// away from the snapshot the images differ and a far rotation can fit better by chance
+    pub fn alignment(snapshot: &Image, current: &Image, max_rotation: f32) -> f32 {
+        let snapshot = snapshot.sample(ALIGNMENT_STEPS);
+        let current = current.sample(ALIGNMENT_STEPS);
+        let difference = |shift: usize| -> f32 {
+            snapshot
+                .iter()
+                .enumerate()
+                .map(|(i, brightness)| {
+                    (current[(i + shift) % ALIGNMENT_STEPS] - brightness).powi(2)
+                })
+                .sum()
+        };
+        let angle = |shift: usize| shift as f32 / ALIGNMENT_STEPS as f32 * 2.0 * PI;
+        let best = (0..ALIGNMENT_STEPS)
+            .filter(|shift| angle(*shift).min(2.0 * PI - angle(*shift)) <= max_rotation)
+            .map(|shift| (shift, difference(shift)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or(0, |(shift, _)| shift);
+        angle(best)
+    }
+}
+
+#[test]
+fn alignment_test() {
+    use crate::Scene;
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+    let snapshot = Image::new(Vec2::<f32>::new(0.0, 0.0), &world.obstacles).unwrap();
+
+    let compass = Compass {
+        offset: 0.5,
+        visual: true,
+        ..Compass::default()
+    };
+
+    // the visual compass finds the rotation again
+    let estimate = Compass::alignment(&snapshot, &snapshot.rotated(0.5), PI);
+    assert!((estimate - 0.5).abs() < 2.0 * PI / ALIGNMENT_STEPS as f32);
+
+    let oriented = compass.orient(Vec2::<f32>::new(0.0, 0.0), &snapshot, snapshot.clone());
+    println!("{:?}", oriented);
+    // the compass error is undone, so every segment of the snapshot is found again
+    for segment in &snapshot.segments {
+        assert_eq!(oriented.color_at(segment.bisector), segment.color);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{HomingError, HomingModel, Image, Retina, Vec2, View};
+
+/// the descent in image distance model by Zeil et al.
+/// the snapshot and the current image are compared pixel by pixel on a retina,
+/// the bee moves down the gradient of the root mean square difference
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct DidModel {
+    /// the retina the images are compared on
+    pub retina: Retina,
+    /// length of the test steps the gradient is estimated with
+    pub displacement: f32,
+}
+
+impl Default for DidModel {
+    /// a retina with an ommatidium every 5 degrees and short test steps
+    fn default() -> Self {
+        DidModel {
+            retina: Retina {
+                ommatidia: 72,
+                acceptance_angle: 5f32.to_radians(),
+                threshold: 0.5,
+            },
+            displacement: 0.1,
+        }
+    }
+}
+
+impl HomingModel for DidModel {
+    /// the homing vector is the negative gradient and not normalized,
+    /// it gets shorter where the image difference is flat
+    /// test steps that end inside an obstacle are taken in the opposite direction instead
+    fn home(&self, snapshot: &Image, view: &View) -> Result<Vec2<f32>, HomingError> {
+        let snapshot = self.retina.project(snapshot);
+        let difference = |image: &Image| self.retina.project(image).difference(&snapshot);
+
+        // take a test step along both axes and compare the differences
+        let here = difference(&view.current);
+        let slope = |step: Vec2<f32>| match view.look(step) {
+            Ok(image) => Ok((difference(&image) - here) / self.displacement),
+            // the bee cant look from inside an obstacle, so it steps back instead
+            Err(HomingError::InsideObstacle { .. }) => {
+                let image = view.look(-1.0 * step)?;
+                Ok((here - difference(&image)) / self.displacement)
+            }
+            Err(err) => Err(err),
+        };
+        let gradient = Vec2::<f32>::new(
+            slope(Vec2::<f32>::new(self.displacement, 0.0))?,
+            slope(Vec2::<f32>::new(0.0, self.displacement))?,
+        );
+
+        Ok(-1.0 * gradient)
+    }
+}
+
+#[test]
+fn did_test() {
+    use crate::{Scene, VectorField};
+
+    let mut scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let model = DidModel::default();
+
+    // close to home the field leads home
+    scene.grid.width = [-1.0, 1.5];
+    scene.grid.height = [-1.0, 1.5];
+    scene.grid.spacing = 0.5;
+    let world = scene.world();
+    let bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+    let near = VectorField::generate(bee, &world)
+        .avg_angular_error
+        .unwrap();
+
+    // a few units out the image difference no longer descends towards home
+    scene.grid.width = [-3.0, 4.0];
+    scene.grid.height = [-3.0, 4.0];
+    scene.grid.spacing = 1.0;
+    let world = scene.world();
+    let bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+    let far = VectorField::generate(bee, &world)
+        .avg_angular_error
+        .unwrap();
+
+    println!("{} {}", near.to_degrees(), far.to_degrees());
+
+    assert!(near < 25f32.to_radians());
+    assert!(far > 60f32.to_radians());
+
+    // right next to a landmark the test step along x ends inside it
+    let mut bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+    bee.position = Vec2::<f32>::new(2.95, 2.0);
+
+    assert!(bee.home(&world).is_ok());
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+
+use crate::Vec2;
+
+/// errors that can occur while projecting the world or calculating a homing vector
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HomingError {
+    /// the position lies inside an obstacle, so there is no image to take
+    InsideObstacle {
+        /// position of the bee
+        position: Vec2<f32>,
+    },
+    /// no obstacle is visible from the position, so the image circle is empty
+    NoLandmarks {
+        /// position of the bee
+        position: Vec2<f32>,
+    },
+    /// a projected segment has a bisector or width that is not a finite number
+    NonFinite {
+        /// position of the bee
+        position: Vec2<f32>,
+    },
+    /// every snapshot segment was left unmatched, so there is nothing to home with
+    NoMatches,
+    /// the bee has no snapshot to compare the current image with
+    NoSnapshot,
+}
+
+impl fmt::Display for HomingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HomingError::InsideObstacle { position } => write!(
+                f,
+                "position ({}, {}) is inside an obstacle",
+                position[0], position[1]
+            ),
+            HomingError::NoLandmarks { position } => write!(
+                f,
+                "no obstacle is visible from position ({}, {})",
+                position[0], position[1]
+            ),
+            HomingError::NonFinite { position } => write!(
+                f,
+                "the image at position ({}, {}) contains a segment that is not finite",
+                position[0], position[1]
+            ),
+            HomingError::NoMatches => write!(f, "no snapshot segment could be matched"),
+            HomingError::NoSnapshot => write!(f, "the bee has no snapshot"),
+        }
+    }
+}
+
+impl std::error::Error for HomingError {}
@@ -0,0 +1,285 @@
+use std::f32::consts::PI;
+
+use crate::{Color, Distance, HomingError, Obstacle, Segment, Vec2};
+
+/// datastructure to hold the Segments
+/// this will be used for the snapshot and the image that is cast onto the retina
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct Image {
+    /// the segments that make up the image circle
+    pub segments: Vec<Segment>,
+}
+
+/// a part of the image circle: (start, end, color)
+/// start and end are radians, 0 <= start < end <= 2Pi
+type Arc = (f32, f32, Color);
+
+/// arcs that are closer than this are treated as touching
+const EPSILON: f32 = 1e-6;
+
+impl Image {
+    /// projects the obstacles onto the image circle of a bee at the given position
+    /// nearer obstacles hide the parts of farther obstacles behind them,
+    /// neighbouring parts of the same color are joined and the gaps between them are filled with white segments
+    /// fails if the position is inside an obstacle or if no obstacle is visible
+    pub fn new(position: Vec2<f32>, obstacles: &[Box<dyn Obstacle>]) -> Result<Image, HomingError> {
+        // there is no image from inside an obstacle
+        if obstacles.iter().any(|obstacle| obstacle.contains(position)) {
+            return Err(HomingError::InsideObstacle { position });
+        }
+        // map every obstacle onto a segment and keep the ones that exist
+        // together with the distance of the obstacle
+        let mut projected: Vec<(f32, Segment)> = obstacles
+            .iter()
+            .filter_map(|obstacle| {
+                obstacle
+                    .map(position)
+                    .map(|segment| (obstacle.distance(position), segment))
+            })
+            .collect();
+
+        // check that there is something to project and sort
+        if projected.is_empty() {
+            return Err(HomingError::NoLandmarks { position });
+        }
+        if projected.iter().any(|(distance, s)| {
+            !distance.is_finite() || !s.bisector.is_finite() || !s.width.is_finite()
+        }) {
+            return Err(HomingError::NonFinite { position });
+        }
+
+        // sort the segments from near to far
+        projected.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // the visible parts of the image circle
+        let mut visible: Vec<Arc> = Vec::new();
+        for (_, segment) in projected {
+            for (start, end) in Image::unwrap(segment) {
+                // clip away everything that is hidden by a nearer segment
+                let mut parts = vec![(start, end)];
+                for &(hidden_start, hidden_end, _) in &visible {
+                    parts = parts
+                        .into_iter()
+                        .flat_map(|(start, end)| {
+                            [(start, end.min(hidden_start)), (start.max(hidden_end), end)]
+                        })
+                        .filter(|(start, end)| end - start > EPSILON)
+                        .collect();
+                }
+                visible.extend(
+                    parts
+                        .into_iter()
+                        .map(|(start, end)| (start, end, segment.color)),
+                );
+            }
+        }
+        visible.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+
+        // fill the spaces between the visible parts with white
+        let mut arcs: Vec<Arc> = Vec::new();
+        let mut edge_prev = 0.0;
+        for arc in visible {
+            if arc.0 - edge_prev > EPSILON {
+                arcs.push((edge_prev, arc.0, Color::WHITE));
+            }
+            edge_prev = arc.1;
+            arcs.push(arc);
+        }
+        if 2.0 * PI - edge_prev > EPSILON {
+            arcs.push((edge_prev, 2.0 * PI, Color::WHITE));
+        }
+
+        // join neighbouring arcs of the same color
+        let mut joined: Vec<Arc> = Vec::new();
+        for arc in arcs {
+            match joined.last_mut() {
+                Some(last) if last.2 == arc.2 => last.1 = arc.1,
+                _ => joined.push(arc),
+            }
+        }
+        // the last and the first arc touch at 0 as well
+        if joined.len() > 1 && joined[0].2 == joined[joined.len() - 1].2 {
+            let first = joined.remove(0);
+            let last = joined.last_mut().unwrap();
+            last.1 = first.1 + 2.0 * PI;
+        }
+
+        // turn the arcs into segments
+        let mut segments: Vec<Segment> = joined
+            .into_iter()
+            .map(|(start, end, color)| {
+                let mut bisector = (start + end) / 2.0;
+                if bisector >= 2.0 * PI {
+                    bisector -= 2.0 * PI;
+                }
+                Segment {
+                    bisector,
+                    width: end - start,
+                    color,
+                }
+            })
+            .collect();
+
+        // sort the segments for easier use later on
+        // this isn't necessary but makes life easier
+        segments.sort_unstable_by(|a, b| a.bisector.total_cmp(&b.bisector));
+
+        Ok(Image { segments })
+    }
+    /// returns the image rotated counter clockwise by the given angle, radians
+    pub fn rotated(&self, angle: f32) -> Image {
+        let mut segments: Vec<Segment> = self
+            .segments
+            .iter()
+            .map(|segment| {
+                let mut bisector = (segment.bisector + angle).rem_euclid(2.0 * PI);
+                if bisector >= 2.0 * PI {
+                    bisector = 0.0;
+                }
+                Segment {
+                    bisector,
+                    ..*segment
+                }
+            })
+            .collect();
+        segments.sort_unstable_by(|a, b| a.bisector.total_cmp(&b.bisector));
+        Image { segments }
+    }
+    /// returns the color of the image circle in the given direction, radians
+    /// directions that no segment covers are white
+    pub fn color_at(&self, angle: f32) -> Color {
+        self.segments
+            .iter()
+            .find(|segment| segment.bisector.dist(angle).abs() <= segment.width / 2.0)
+            .map_or(Color::WHITE, |segment| segment.color)
+    }
+    /// samples the brightness of the image circle in n evenly spaced directions starting at 0
+    pub fn sample(&self, n: usize) -> Vec<f32> {
+        (0..n)
+            .map(|i| self.color_at(i as f32 / n as f32 * 2.0 * PI).0)
+            .collect()
+    }
+    /// root mean square difference in brightness between two images, sampled every degree
+    pub fn difference(&self, other: &Image) -> f32 {
+        let sum: f32 = self
+            .sample(360)
+            .iter()
+            .zip(other.sample(360))
+            .map(|(a, b)| (a - b).powi(2))
+            .sum();
+        (sum / 360.0).sqrt()
+    }
+    /// turns a segment into its edges on the image circle
+    /// segments that cross 0 are split in two, so that all edges are in 0..2Pi
+    fn unwrap(segment: Segment) -> Vec<(f32, f32)> {
+        let mut start = (segment.bisector - segment.width / 2.0).rem_euclid(2.0 * PI);
+        if start >= 2.0 * PI {
+            start = 0.0;
+        }
+        let end = start + segment.width;
+        if end > 2.0 * PI {
+            vec![(start, 2.0 * PI), (0.0, end - 2.0 * PI)]
+        } else {
+            vec![(start, end)]
+        }
+    }
+}
+
+#[test]
+fn image_new_test() {
+    use crate::Circle;
+
+    let circle1 = Circle::new(Vec2::<f32>::new(3.5, 2.0), 0.5);
+    let circle2 = Circle::new(Vec2::<f32>::new(3.5, -2.0), 0.5);
+    let circle3 = Circle::new(Vec2::<f32>::new(0.0, -4.0), 0.5);
+
+    let obstacles: Vec<Box<dyn Obstacle>> =
+        vec![Box::new(circle1), Box::new(circle2), Box::new(circle3)];
+
+    let image = Image::new(Vec2::<f32>::new(0.0, 0.0), &obstacles).unwrap();
+
+    println!("{:?}", image);
+
+    let mut sum = 0.0;
+    for segment in image.segments {
+        sum += segment.width;
+    }
+
+    println!("total size: {}", sum);
+
+    assert!((sum - (PI * 2.0)).abs() < 0.01);
+}
+
+#[test]
+fn image_error_test() {
+    use crate::Circle;
+
+    let obstacles: Vec<Box<dyn Obstacle>> =
+        vec![Box::new(Circle::new(Vec2::<f32>::new(1.0, 0.0), 0.5))];
+
+    assert_eq!(
+        Image::new(Vec2::<f32>::new(1.2, 0.0), &obstacles),
+        Err(HomingError::InsideObstacle {
+            position: Vec2::<f32>::new(1.2, 0.0)
+        })
+    );
+    assert_eq!(
+        Image::new(Vec2::<f32>::new(0.0, 0.0), &[]),
+        Err(HomingError::NoLandmarks {
+            position: Vec2::<f32>::new(0.0, 0.0)
+        })
+    );
+}
+
+#[test]
+fn image_occlusion_test() {
+    use crate::Circle;
+
+    // the far circle is completely hidden behind the near one
+    let near: Box<dyn Obstacle> = Box::new(Circle::new(Vec2::<f32>::new(1.0, 0.0), 0.5));
+    let far: Box<dyn Obstacle> = Box::new(Circle::new(Vec2::<f32>::new(3.0, 0.0), 0.5));
+
+    let position = Vec2::<f32>::new(0.0, 0.0);
+    let both = Image::new(position, &[near, far]).unwrap();
+    let near: Box<dyn Obstacle> = Box::new(Circle::new(Vec2::<f32>::new(1.0, 0.0), 0.5));
+    let alone = Image::new(position, &[near]).unwrap();
+
+    println!("{:?}", both);
+
+    assert_eq!(both.segments.len(), 2);
+    for (a, b) in both.segments.iter().zip(alone.segments.iter()) {
+        assert!((a.bisector - b.bisector).abs() < 1e-4);
+        assert!((a.width - b.width).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn image_color_test() {
+    use crate::Circle;
+
+    // a near black circle partially hides a far gray one
+    let gray = Color(0.5);
+    let obstacles: Vec<Box<dyn Obstacle>> = vec![
+        Box::new(Circle::new(Vec2::<f32>::new(2.0, 0.0), 0.5)),
+        Box::new(Circle::new(Vec2::<f32>::new(4.0, 1.0), 1.5).with_color(gray)),
+    ];
+
+    let image = Image::new(Vec2::<f32>::new(0.0, 0.0), &obstacles).unwrap();
+
+    println!("{:?}", image);
+
+    let colors: Vec<Color> = image.segments.iter().map(|s| s.color).collect();
+
+    assert!(colors.contains(&Color::BLACK));
+    assert!(colors.contains(&gray));
+    assert!(colors.contains(&Color::WHITE));
+
+    // the black circle is not cut by the gray one behind it
+    let black = image
+        .segments
+        .iter()
+        .find(|s| s.color == Color::BLACK)
+        .unwrap();
+
+    assert!((black.width - 2.0 * (0.5f32 / 2.0).asin()).abs() < 1e-4);
+}
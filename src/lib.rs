@@ -0,0 +1,49 @@
+//! implementation of the snapshot homing algorithm described in the paper
+//! "How honey bees use landmarks to guide their return to a food source"
+//! by B. A. Cartwright & T. S. Collett
+//!
+//! a World holds obstacles that are projected onto the image circle of a Bee,
+//! the Bee compares its snapshot with the current image to get a homing vector
+//! and a VectorField samples those homing vectors on the Grid of the World
+
+mod alv;
+mod bee;
+mod catchment;
+mod compass;
+mod did;
+mod error;
+mod image;
+mod matching;
+mod model;
+mod obstacle;
+mod retina;
+mod route;
+mod scene;
+mod segment;
+mod snapshot;
+mod trajectory;
+mod vec2;
+mod vector_field;
+mod warping;
+mod world;
+
+pub use alv::AlvModel;
+pub use bee::Bee;
+pub use catchment::CatchmentArea;
+pub use compass::Compass;
+pub use did::DidModel;
+pub use error::HomingError;
+pub use image::Image;
+pub use matching::SegmentMatching;
+pub use model::{ColorMatching, HomingModel, SnapshotModel, View, Weights};
+pub use obstacle::{Circle, Obstacle, Shape};
+pub use retina::{PixelImage, Retina};
+pub use route::{Route, RouteTraversal};
+pub use scene::{GridDescription, ObstacleDescription, Scene, SceneError};
+pub use segment::{Color, Distance, Segment};
+pub use snapshot::{Snapshot, SnapshotSelection};
+pub use trajectory::{Outcome, Simulation, Trajectory};
+pub use vec2::Vec2;
+pub use vector_field::VectorField;
+pub use warping::WarpingModel;
+pub use world::{Grid, World};
@@ -0,0 +1,252 @@
+use std::{f32::consts::PI, str::FromStr};
+
+use crate::{ColorMatching, Distance, Image, Segment};
+
+/// policy for pairing the segments of the snapshot with the segments of the current image
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Debug)]
+pub enum SegmentMatching {
+    /// every snapshot segment is matched with its closest segment on its own,
+    /// so several snapshot segments can share the same segment of the current image
+    Independent,
+    /// every segment of the current image is matched at most once,
+    /// the pairs are chosen so that the total angular distance is minimal
+    OneToOne,
+}
+
+impl SegmentMatching {
+    /// pairs the segments of the snapshot with the segments of the current image
+    /// the pairs are returned as (snapshot segment, current segment) in the order of the snapshot
+    /// a snapshot segment is left unmatched if its partner would be farther away than max_distance (radians),
+    /// if there is no candidate of its color, e.g. because the landmark is hidden behind a nearer one,
+    /// or, with one to one matching, if there are not enough candidates
+    pub fn pairs(
+        &self,
+        color_matching: ColorMatching,
+        max_distance: Option<f32>,
+        snapshot: &Image,
+        current: &Image,
+    ) -> Vec<(Segment, Option<Segment>)> {
+        let max_distance = max_distance.unwrap_or(f32::INFINITY);
+        // the segments of the current image every snapshot segment can be matched with
+        let candidates: Vec<Vec<Segment>> = snapshot
+            .segments
+            .iter()
+            .map(|snapshot_segment| {
+                color_matching.candidates(snapshot_segment.color, &current.segments)
+            })
+            .collect();
+
+        match self {
+            SegmentMatching::Independent => snapshot
+                .segments
+                .iter()
+                .zip(candidates)
+                .map(|(snapshot_segment, candidates)| {
+                    // without candidates the snapshot segment stays unmatched
+                    let Some(&first) = candidates.first() else {
+                        return (*snapshot_segment, None);
+                    };
+                    // take the first one
+                    let mut best_match_so_far = first;
+                    // check all other segments with a matching color:
+                    for retinal_segment in candidates {
+                        if snapshot_segment.dist(retinal_segment).abs()
+                            < snapshot_segment.dist(best_match_so_far).abs()
+                        {
+                            best_match_so_far = retinal_segment;
+                        }
+                    }
+                    // save the tuple of matched segments if they are close enough
+                    let matched = snapshot_segment.dist(best_match_so_far).abs() <= max_distance;
+                    (*snapshot_segment, matched.then_some(best_match_so_far))
+                })
+                .collect(),
+            SegmentMatching::OneToOne => {
+                // pairs that are not allowed or too far apart cost more than any set of allowed pairs,
+                // so as many snapshot segments as possible get a partner
+                let forbidden = 2.0 * PI * (snapshot.segments.len() + 1) as f32;
+                let cost: Vec<Vec<f32>> = snapshot
+                    .segments
+                    .iter()
+                    .zip(&candidates)
+                    .map(|(snapshot_segment, candidates)| {
+                        current
+                            .segments
+                            .iter()
+                            .map(|retinal_segment| {
+                                let distance = snapshot_segment.dist(*retinal_segment).abs();
+                                if candidates.contains(retinal_segment) && distance <= max_distance
+                                {
+                                    distance
+                                } else {
+                                    forbidden
+                                }
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                assign(&cost)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(row, column)| {
+                        let matched = column
+                            .filter(|&column| cost[row][column] < forbidden)
+                            .map(|column| current.segments[column]);
+                        (snapshot.segments[row], matched)
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+impl FromStr for SegmentMatching {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "independent" => Ok(SegmentMatching::Independent),
+            "one-to-one" => Ok(SegmentMatching::OneToOne),
+            _ => Err(format!(
+                "unknown segment matching `{}`, expected `independent` or `one-to-one`",
+                s
+            )),
+        }
+    }
+}
+
+/// assigns every row of the cost matrix to a different column so that the total cost is minimal
+/// returns the column of every row, rows are left out if there are more rows than columns
+fn assign(cost: &[Vec<f32>]) -> Vec<Option<usize>> {
+    let rows = cost.len();
+    let columns = cost.first().map_or(0, |row| row.len());
+    if rows > columns {
+        // assign the columns to the rows instead and turn the result around
+        let transposed: Vec<Vec<f32>> = (0..columns)
+            .map(|column| cost.iter().map(|row| row[column]).collect())
+            .collect();
+        let mut assignment = vec![None; rows];
+        for (column, row) in assign(&transposed).into_iter().enumerate() {
+            if let Some(row) = row {
+                assignment[row] = Some(column);
+            }
+        }
+        return assignment;
+    }
+
+    // hungarian algorithm with potentials
+    // index 0 is a virtual column that starts every augmenting path
+    let mut row_potential = vec![0.0; rows + 1];
+    let mut column_potential = vec![0.0; columns + 1];
+    // row assigned to every column, 0 if the column is free
+    let mut assigned = vec![0; columns + 1];
+    // previous column on the augmenting path
+    let mut previous = vec![0; columns + 1];
+    for row in 1..=rows {
+        assigned[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f32::INFINITY; columns + 1];
+        let mut used = vec![false; columns + 1];
+        // grow the path until it reaches a free column
+        while assigned[column] != 0 {
+            used[column] = true;
+            let current_row = assigned[column];
+            let mut delta = f32::INFINITY;
+            let mut next = 0;
+            for j in 1..=columns {
+                if used[j] {
+                    continue;
+                }
+                let slack =
+                    cost[current_row - 1][j - 1] - row_potential[current_row] - column_potential[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    previous[j] = column;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    next = j;
+                }
+            }
+            for j in 0..=columns {
+                if used[j] {
+                    row_potential[assigned[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+            column = next;
+        }
+        // flip the assignments along the path
+        while column != 0 {
+            let prev = previous[column];
+            assigned[column] = assigned[prev];
+            column = prev;
+        }
+    }
+
+    let mut assignment = vec![None; rows];
+    for (column, &row) in assigned.iter().enumerate().skip(1) {
+        if row != 0 {
+            assignment[row - 1] = Some(column - 1);
+        }
+    }
+    assignment
+}
+
+#[test]
+fn assign_test() {
+    // the greedy choice of the first row would force the second row onto an expensive column
+    let cost = vec![vec![1.0, 2.0, 9.0], vec![1.0, 8.0, 9.0]];
+
+    assert_eq!(assign(&cost), vec![Some(1), Some(0)]);
+
+    // more rows than columns leaves the most expensive row out
+    let cost = vec![vec![1.0], vec![0.5], vec![3.0]];
+
+    assert_eq!(assign(&cost), vec![None, Some(0), None]);
+}
+
+#[test]
+fn max_distance_test() {
+    use crate::Color;
+
+    let segment = |bisector| Segment {
+        bisector,
+        width: 0.5,
+        color: Color::BLACK,
+    };
+    let snapshot = Image {
+        segments: vec![segment(0.0), segment(3.0)],
+    };
+    let current = Image {
+        segments: vec![segment(0.2), segment(4.0)],
+    };
+
+    for matching in [SegmentMatching::Independent, SegmentMatching::OneToOne] {
+        let all = matching.pairs(ColorMatching::Exact, None, &snapshot, &current);
+        let close = matching.pairs(ColorMatching::Exact, Some(0.5), &snapshot, &current);
+
+        assert!(all.iter().all(|(_, matched)| matched.is_some()));
+        assert_eq!(close[0].1, Some(segment(0.2)));
+        assert_eq!(close[1].1, None);
+    }
+
+    // a gray landmark hidden behind another one has no candidate, the others are still matched
+    let gray = Segment {
+        color: Color(0.5),
+        ..segment(1.5)
+    };
+    let snapshot = Image {
+        segments: vec![segment(0.0), gray],
+    };
+
+    for matching in [SegmentMatching::Independent, SegmentMatching::OneToOne] {
+        let pairs = matching.pairs(ColorMatching::Exact, None, &snapshot, &current);
+
+        assert_eq!(pairs[0].1, Some(segment(0.2)));
+        assert_eq!(pairs[1].1, None);
+    }
+}
@@ -0,0 +1,316 @@
+use std::{f32::consts::PI, str::FromStr};
+
+use crate::{
+    Bee, Color, Distance, HomingError, Image, Segment, SegmentMatching, Snapshot, Vec2, World,
+};
+
+/// trait for homing models
+/// a homing model compares the snapshot with what the bee sees and tells the bee where to go
+pub trait HomingModel {
+    /// calculates the homing vector from the snapshot and the view at the current position
+    fn home(&self, snapshot: &Image, view: &View) -> Result<Vec2<f32>, HomingError>;
+    /// counts the snapshot segments that are left out of the homing vector
+    /// models that do not match segments never leave any out
+    fn unmatched(&self, _snapshot: &Image, _current: &Image) -> Result<usize, HomingError> {
+        Ok(0)
+    }
+}
+
+/// what a bee sees at its current position
+/// most models only need the current image,
+/// others can look from positions close by as well
+pub struct View<'a> {
+    /// the image at the current position
+    pub current: Image,
+    /// the bee that is looking
+    bee: &'a Bee,
+    /// the world the bee is looking at
+    world: &'a World,
+    /// the snapshot the images are aligned with
+    snapshot: &'a Snapshot,
+}
+
+impl<'a> View<'a> {
+    /// takes the image at the current position of the bee, aligned with the given snapshot
+    pub fn new(
+        bee: &'a Bee,
+        world: &'a World,
+        snapshot: &'a Snapshot,
+    ) -> Result<View<'a>, HomingError> {
+        Ok(View {
+            current: bee.image_at(bee.position, world, snapshot)?,
+            bee,
+            world,
+            snapshot,
+        })
+    }
+    /// takes the image the bee would see if it was moved by the given offset
+    pub fn look(&self, offset: Vec2<f32>) -> Result<Image, HomingError> {
+        self.bee
+            .image_at(self.bee.position + offset, self.world, self.snapshot)
+    }
+}
+
+/// the snapshot model by Cartwright & Collett
+/// every snapshot segment is matched with a segment of the current image,
+/// each pair contributes a turning and a positioning vector,
+/// either of unit length or proportional to the difference of the pair
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct SnapshotModel {
+    /// weights of the vectors contributed by dark snapshot segments, the landmarks of any color
+    pub dark: Weights,
+    /// weights of the vectors contributed by light snapshot segments, the white gaps between landmarks
+    pub light: Weights,
+    /// which segments of the current image a snapshot segment can be matched with
+    pub color_matching: ColorMatching,
+    /// how the segments of the snapshot are paired with the segments of the current image
+    pub segment_matching: SegmentMatching,
+    /// largest angular distance between two matched segments, radians
+    /// snapshot segments without a partner this close are unmatched and contribute nothing
+    /// None matches segments at any distance
+    pub max_distance: Option<f32>,
+    /// scale the vectors of each pair by the difference in bisector and width
+    /// instead of only using the sign, the homing vector is then not normalized
+    pub proportional: bool,
+}
+
+/// weights of the turning and positioning vectors in the homing vector
+/// a weight of zero leaves the vectors out
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Weights {
+    /// weight of the turning vectors
+    pub turning: f32,
+    /// weight of the positioning vectors
+    pub positioning: f32,
+}
+
+/// policy for matching segments of different colors
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Debug)]
+pub enum ColorMatching {
+    /// only segments with the same color are matched
+    Exact,
+    /// segments are matched with the segments that are closest in color
+    Nearest,
+}
+
+impl Default for SnapshotModel {
+    /// the weights used in the paper
+    fn default() -> Self {
+        let weights = Weights {
+            turning: 1.0,
+            positioning: 3.0,
+        };
+        SnapshotModel {
+            dark: weights,
+            light: weights,
+            color_matching: ColorMatching::Exact,
+            segment_matching: SegmentMatching::Independent,
+            max_distance: None,
+            proportional: false,
+        }
+    }
+}
+
+impl SnapshotModel {
+    /// returns the weights for the vectors of a snapshot segment with the given color
+    /// landmarks get the dark weights and the gaps between them the light weights
+    pub fn weights(&self, color: Color) -> Weights {
+        if color.is_landmark() {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+}
+
+impl HomingModel for SnapshotModel {
+    fn home(&self, snapshot: &Image, view: &View) -> Result<Vec2<f32>, HomingError> {
+        let current = &view.current;
+        // pair the segments of the snapshot with the segments of the current image
+        // unmatched snapshot segments are left out
+        let matched: Vec<(Segment, Segment)> = self
+            .segment_matching
+            .pairs(self.color_matching, self.max_distance, snapshot, current)
+            .into_iter()
+            .filter_map(|(snap_segment, ret_segment)| Some((snap_segment, ret_segment?)))
+            .collect();
+        if matched.is_empty() {
+            return Err(HomingError::NoMatches);
+        }
+        // generate turning vector
+        let mut turning_vec = Vec2::<f32>::new(0.0, 0.0);
+        matched.iter().for_each(|(snap_segment, ret_segment)| {
+            // get angular difference
+            let mut diff = if self.proportional {
+                ret_segment.dist(*snap_segment)
+            } else if ret_segment.dist(*snap_segment) < 0.0 {
+                -1.0 // point clockwise
+            } else {
+                1.0 // point counter clockwise
+            };
+
+            if ret_segment.width > PI {
+                diff = -diff;
+            }
+
+            // generate the vector
+            let vec = Vec2::<f32>::new(
+                (ret_segment.bisector - PI / 2.0).cos() * diff,
+                (ret_segment.bisector - PI / 2.0).sin() * diff,
+            );
+            turning_vec += self.weights(snap_segment.color).turning * vec;
+        });
+        // generate positioning vector
+        let mut positioning_vec = Vec2::<f32>::new(0.0, 0.0);
+        matched.iter().for_each(|(snap_segment, ret_segment)| {
+            // get size difference
+            let diff = if self.proportional {
+                snap_segment.width - ret_segment.width
+            } else if snap_segment.width > ret_segment.width {
+                1.0 // point away from the retinal bisector
+            } else {
+                -1.0 // point towards the center of the retina from the bisector
+            };
+            // generate the vector
+            let vec = Vec2::<f32>::new(
+                ret_segment.bisector.cos() * diff,
+                ret_segment.bisector.sin() * diff,
+            );
+            positioning_vec += self.weights(snap_segment.color).positioning * vec
+        });
+        // generate homing vector
+        let final_vec = turning_vec + positioning_vec;
+        if self.proportional {
+            Ok(final_vec)
+        } else {
+            Ok(final_vec.normalized())
+        }
+    }
+    fn unmatched(&self, snapshot: &Image, current: &Image) -> Result<usize, HomingError> {
+        let pairs =
+            self.segment_matching
+                .pairs(self.color_matching, self.max_distance, snapshot, current);
+        Ok(pairs
+            .iter()
+            .filter(|(_, matched)| matched.is_none())
+            .count())
+    }
+}
+
+impl ColorMatching {
+    /// returns the segments that a segment with the given color can be matched with
+    pub fn candidates(&self, color: Color, segments: &[Segment]) -> Vec<Segment> {
+        match self {
+            ColorMatching::Exact => segments
+                .iter()
+                .filter(|s| s.color == color)
+                .copied()
+                .collect(),
+            ColorMatching::Nearest => {
+                // the smallest difference in color to any segment
+                let closest = segments
+                    .iter()
+                    .map(|s| s.color.difference(color))
+                    .fold(f32::INFINITY, f32::min);
+                segments
+                    .iter()
+                    .filter(|s| s.color.difference(color) <= closest)
+                    .copied()
+                    .collect()
+            }
+        }
+    }
+}
+
+impl FromStr for ColorMatching {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "exact" => Ok(ColorMatching::Exact),
+            "nearest" => Ok(ColorMatching::Nearest),
+            _ => Err(format!(
+                "unknown color matching `{}`, expected `exact` or `nearest`",
+                s
+            )),
+        }
+    }
+}
+
+#[test]
+fn proportional_test() {
+    use crate::Scene;
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    let model = SnapshotModel {
+        proportional: true,
+        ..SnapshotModel::default()
+    };
+    let bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+    let error = crate::VectorField::generate(bee, &world)
+        .avg_angular_error
+        .unwrap();
+
+    let mut bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+
+    bee.position = Vec2::<f32>::new(5.0, -5.0);
+    let far = bee.home(&world).unwrap();
+
+    bee.position = Vec2::<f32>::new(0.5, -0.5);
+    let near = bee.home(&world).unwrap();
+
+    println!("{} {:?} {:?}", error.to_degrees(), far, near);
+
+    // the field leads home across the whole arena and the vectors get shorter closer to home
+    assert!(error < 15f32.to_radians());
+    assert!(near.len() < far.len());
+}
+
+#[test]
+fn weights_test() {
+    use crate::Scene;
+
+    // the gray landmark is as much a landmark as the black one
+    let scene = Scene::parse(include_str!("../scenes/colors.toml")).unwrap();
+    let world = scene.world();
+    let mut bee = scene.bee(&world).unwrap();
+    bee.position = Vec2::<f32>::new(5.0, -4.0);
+
+    let view = View::new(&bee, &world, &bee.snapshots[0]).unwrap();
+    let snapshot = &bee.snapshots[0].image;
+    // the snapshot with only the landmarks or only the gaps
+    let only = |landmarks: bool| Image {
+        segments: snapshot
+            .segments
+            .iter()
+            .filter(|s| s.color.is_landmark() == landmarks)
+            .copied()
+            .collect(),
+    };
+    let none = Weights {
+        turning: 0.0,
+        positioning: 0.0,
+    };
+    let default = SnapshotModel::default();
+
+    let landmarks = SnapshotModel {
+        light: none,
+        ..default
+    }
+    .home(snapshot, &view)
+    .unwrap();
+    let gaps = SnapshotModel {
+        dark: none,
+        ..default
+    }
+    .home(snapshot, &view)
+    .unwrap();
+
+    println!("{:?} {:?}", landmarks, gaps);
+
+    assert!((landmarks - default.home(&only(true), &view).unwrap()).len() < 1e-5);
+    assert!((gaps - default.home(&only(false), &view).unwrap()).len() < 1e-5);
+    assert!((landmarks - gaps).len() > 1e-3);
+}
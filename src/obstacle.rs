@@ -0,0 +1,111 @@
+use std::f32::consts::PI;
+
+use crate::{Color, Segment, Vec2};
+
+/// trait for obstacles
+/// all obstacles will have to implement this trait
+pub trait Obstacle {
+    /// maps the obstacle from a position to a Segment
+    fn map(&self, position: Vec2<f32>) -> Option<Segment>;
+    /// checks if the position lies inside the obstacle
+    fn contains(&self, position: Vec2<f32>) -> bool;
+    /// distance from the position to the closest point of the obstacle
+    /// nearer obstacles hide farther ones on the image circle
+    fn distance(&self, position: Vec2<f32>) -> f32;
+    /// returns the outline of the obstacle in world coordinates
+    /// this is used for drawing the obstacle
+    fn shape(&self) -> Shape;
+}
+
+/// outline of an obstacle that can be drawn
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Shape {
+    /// a filled circle
+    Circle {
+        /// center of the circle
+        center: Vec2<f32>,
+        /// radius of the circle
+        radius: f32,
+        /// color the circle is filled with
+        color: Color,
+    },
+}
+
+/// obstacle struct for circular objects
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Circle {
+    /// center of the circle
+    pub position: Vec2<f32>,
+    /// radius of the circle
+    pub radius: f32,
+    /// color of the circle
+    pub color: Color,
+}
+
+impl Circle {
+    /// creates a new black circle with the given center and radius
+    pub fn new(position: Vec2<f32>, radius: f32) -> Circle {
+        Circle {
+            position,
+            radius,
+            color: Color::BLACK,
+        }
+    }
+    /// sets the color of the circle
+    pub fn with_color(mut self, color: Color) -> Circle {
+        self.color = color;
+        self
+    }
+}
+
+impl Obstacle for Circle {
+    fn map(&self, position: Vec2<f32>) -> Option<Segment> {
+        // get a vector from the origin
+        let vec = self.position - position;
+
+        // check whether the position is inside the obstacle
+        if vec.len() >= self.radius {
+            // get the angle of the vector to the x-axis
+            // this gives the bisector of the segment
+            let mut bisector = vec[1].atan2(vec[0]);
+            if bisector < 0.0 {
+                bisector += 2.0 * PI;
+            }
+            // calculate the width of the segment
+            let width = (self.radius / vec.len()).asin() * 2.0;
+
+            Some(Segment {
+                bisector,
+                width,
+                color: self.color,
+            })
+        } else {
+            // if it is in the obstacle return nothing
+            None
+        }
+    }
+    fn contains(&self, position: Vec2<f32>) -> bool {
+        (self.position - position).len() < self.radius
+    }
+    fn distance(&self, position: Vec2<f32>) -> f32 {
+        (self.position - position).len() - self.radius
+    }
+    fn shape(&self) -> Shape {
+        Shape::Circle {
+            center: self.position,
+            radius: self.radius,
+            color: self.color,
+        }
+    }
+}
+
+#[test]
+fn segment_map_test() {
+    let circle = Circle::new(Vec2::<f32>::new(-1.0, 1.0), 0.5);
+
+    let segment = circle.map(Vec2::<f32>::new(0.0, 0.0)).unwrap();
+
+    println!("{:?}", segment);
+
+    assert!((segment.bisector - (PI / 4.0) * 3.0).abs() < 0.01);
+}
@@ -0,0 +1,220 @@
+use std::f32::consts::{PI, SQRT_2};
+
+use crate::{Color, Distance, HomingError, Image, Obstacle, Segment, Vec2};
+
+/// a panoramic retina made of evenly spaced ommatidia
+/// the analytic image circle is sampled by every ommatidium with a gaussian sensitivity
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Retina {
+    /// number of ommatidia around the image circle, the first one looks in direction 0
+    pub ommatidia: usize,
+    /// full width at half maximum of the gaussian sensitivity of an ommatidium, radians
+    /// an acceptance angle of 0 samples only the viewing direction
+    pub acceptance_angle: f32,
+    /// brightness below which a pixel is dark when converting back to segments
+    pub threshold: f32,
+}
+
+/// the brightness seen by every ommatidium of a retina
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct PixelImage {
+    /// brightness of the ommatidia, counter clockwise starting at direction 0
+    pub pixels: Vec<f32>,
+}
+
+impl Retina {
+    /// renders the obstacles onto the retina of a bee at the given position
+    /// fails for the same reasons as Image::new
+    pub fn render(
+        &self,
+        position: Vec2<f32>,
+        obstacles: &[Box<dyn Obstacle>],
+    ) -> Result<PixelImage, HomingError> {
+        Ok(self.project(&Image::new(position, obstacles)?))
+    }
+    /// samples the analytic image with every ommatidium
+    pub fn project(&self, image: &Image) -> PixelImage {
+        // standard deviation of the gaussian from its full width at half maximum
+        let sigma = self.acceptance_angle / (2.0 * (2.0 * 2f32.ln()).sqrt());
+        let pixels = (0..self.ommatidia)
+            .map(|i| {
+                let direction = i as f32 / self.ommatidia as f32 * 2.0 * PI;
+                if sigma <= 0.0 {
+                    return image.color_at(direction).0;
+                }
+                // every segment contributes the part of the gaussian it covers
+                image
+                    .segments
+                    .iter()
+                    .map(|segment| {
+                        let offset = direction.dist(segment.bisector);
+                        // the neighbouring turns catch the parts that wrap around
+                        let weight: f32 = [-2.0 * PI, 0.0, 2.0 * PI]
+                            .iter()
+                            .map(|turn| {
+                                let start = offset + turn - segment.width / 2.0;
+                                let end = offset + turn + segment.width / 2.0;
+                                normal_cdf(end / sigma) - normal_cdf(start / sigma)
+                            })
+                            .sum();
+                        weight * segment.color.0
+                    })
+                    .sum()
+            })
+            .collect();
+        PixelImage { pixels }
+    }
+    /// samples the analytic image and turns the pixels back into segments
+    pub fn resample(&self, image: &Image) -> Image {
+        self.project(image).segments(self.threshold)
+    }
+}
+
+impl PixelImage {
+    /// angle between two neighbouring ommatidia, radians
+    pub fn spacing(&self) -> f32 {
+        2.0 * PI / self.pixels.len() as f32
+    }
+    /// root mean square difference in brightness between two pixel images of the same retina
+    pub fn difference(&self, other: &PixelImage) -> f32 {
+        let sum: f32 = self
+            .pixels
+            .iter()
+            .zip(&other.pixels)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum();
+        (sum / self.pixels.len() as f32).sqrt()
+    }
+    /// turns the pixels into black and white segments
+    /// pixels darker than the threshold are black, neighbouring pixels of the same color are joined
+    pub fn segments(&self, threshold: f32) -> Image {
+        let color = |brightness: f32| {
+            if brightness < threshold {
+                Color::BLACK
+            } else {
+                Color::WHITE
+            }
+        };
+        let spacing = self.spacing();
+
+        // runs of pixels with the same color: (first pixel, number of pixels, color)
+        let mut runs: Vec<(usize, usize, Color)> = Vec::new();
+        for (i, brightness) in self.pixels.iter().enumerate() {
+            let color = color(*brightness);
+            match runs.last_mut() {
+                Some(last) if last.2 == color => last.1 += 1,
+                _ => runs.push((i, 1, color)),
+            }
+        }
+        // the last and the first run touch at 0 as well
+        if runs.len() > 1 && runs[0].2 == runs[runs.len() - 1].2 {
+            let first = runs.remove(0);
+            runs.last_mut().unwrap().1 += first.1;
+        }
+
+        // every pixel covers half the spacing on each side of its direction
+        let mut segments: Vec<Segment> = runs
+            .into_iter()
+            .map(|(first, count, color)| {
+                let start = (first as f32 - 0.5) * spacing;
+                let width = count as f32 * spacing;
+                Segment {
+                    bisector: (start + width / 2.0).rem_euclid(2.0 * PI),
+                    width,
+                    color,
+                }
+            })
+            .collect();
+        segments.sort_unstable_by(|a, b| a.bisector.total_cmp(&b.bisector));
+
+        Image { segments }
+    }
+}
+
+/// cumulative distribution function of the standard normal distribution
+/// uses the approximation of the error function by Abramowitz & Stegun (7.1.26)
+fn normal_cdf(x: f32) -> f32 {
+    let z = x.abs() / SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * z);
+    let polynomial = t
+        * (0.254_829_6
+            + t * (-0.284_496_74 + t * (1.421_413_7 + t * (-1.453_152 + t * 1.061_405_4))));
+    let erf = 1.0 - polynomial * (-z * z).exp();
+    if x >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+#[test]
+fn retina_test() {
+    use crate::Scene;
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+    let image = Image::new(Vec2::<f32>::new(0.0, 0.0), &world.obstacles).unwrap();
+
+    let retina = Retina {
+        ommatidia: 360,
+        acceptance_angle: 0.0,
+        threshold: 0.5,
+    };
+    let resampled = retina.resample(&image);
+
+    println!("{:?}", resampled);
+
+    // a fine retina keeps the segments up to the size of a pixel
+    assert_eq!(resampled.segments.len(), image.segments.len());
+    for segment in &image.segments {
+        assert_eq!(resampled.color_at(segment.bisector), segment.color);
+    }
+
+    // a blurry retina with few ommatidia loses the small landmarks
+    let coarse = Retina {
+        ommatidia: 12,
+        acceptance_angle: 30f32.to_radians(),
+        threshold: 0.5,
+    };
+    let resampled = coarse.resample(&image);
+
+    println!("{:?}", resampled);
+
+    assert!(resampled.segments.len() < image.segments.len());
+}
+
+#[test]
+fn blur_test() {
+    // a landmark narrower than the acceptance angle on a white background
+    let width = 1f32.to_radians();
+    let image = Image {
+        segments: vec![
+            Segment {
+                bisector: 0.0,
+                width,
+                color: Color::BLACK,
+            },
+            Segment {
+                bisector: PI,
+                width: 2.0 * PI - width,
+                color: Color::WHITE,
+            },
+        ],
+    };
+    let retina = Retina {
+        ommatidia: 360,
+        acceptance_angle: 10f32.to_radians(),
+        threshold: 0.5,
+    };
+    let pixels = retina.project(&image);
+
+    println!("{:?}", &pixels.pixels[..8]);
+
+    // the blur spreads the darkness of the landmark but keeps all of it
+    let darkness: f32 = pixels.pixels.iter().map(|p| 1.0 - p).sum::<f32>() * pixels.spacing();
+    assert!((darkness - width).abs() < 1e-3);
+    // the ommatidium looking at the landmark sees the part of the gaussian it covers
+    let sigma = retina.acceptance_angle / (2.0 * (2.0 * 2f32.ln()).sqrt());
+    let covered = 2.0 * normal_cdf(width / 2.0 / sigma) - 1.0;
+    assert!((pixels.pixels[0] - (1.0 - covered)).abs() < 1e-3);
+}
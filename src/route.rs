@@ -0,0 +1,160 @@
+use crate::{Bee, Outcome, Simulation, SnapshotSelection, Vec2, World};
+
+/// settings for following a route of snapshots
+/// the bee visits its snapshots after the first one in order and finally the first one at home,
+/// it moves on to the next snapshot once the current image is close enough to the active one
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Route {
+    /// image difference to the active snapshot below which the bee moves on to the next one
+    pub threshold: f32,
+    /// distance the bee moves in one step
+    pub step_length: f32,
+    /// maximum number of steps
+    pub max_steps: usize,
+}
+
+/// the result of following a route
+#[derive(Clone, PartialEq, Debug)]
+pub struct RouteTraversal {
+    /// every position of the bee, starting at the start position
+    pub path: Vec<Vec2<f32>>,
+    /// number of snapshots the bee reached in order
+    pub reached: usize,
+    /// why the bee stopped, Outcome::Arrived once it recognized the last snapshot
+    pub outcome: Outcome,
+}
+
+impl Default for Route {
+    fn default() -> Self {
+        Route {
+            threshold: 0.25,
+            step_length: 0.5,
+            max_steps: 100,
+        }
+    }
+}
+
+impl Route {
+    /// moves the bee from its position along the route
+    /// stops once the last snapshot is reached or for the same reasons as a trajectory,
+    /// after the maximum number of steps, outside the arena, inside an obstacle
+    /// or when the bee cant calculate a homing vector
+    pub fn follow(&self, bee: &mut Bee, world: &World) -> RouteTraversal {
+        // the snapshot at home is the last one of the route
+        let order: Vec<usize> = (1..bee.snapshots.len()).chain([0]).collect();
+        // the route ends by recognizing the last snapshot, not by getting close to home
+        let simulation = Simulation {
+            step_length: self.step_length,
+            max_steps: self.max_steps,
+            arrival_radius: 0.0,
+        };
+        let selection = bee.selection;
+        let home = bee.home;
+        let mut path = vec![bee.position];
+        let mut reached = 0;
+
+        let outcome = simulation.walk(world, home, &mut path, |position| {
+            bee.position = position;
+            // move on to the next snapshots as long as they look like the current image
+            while let Some(&index) = order.get(reached) {
+                let snapshot = &bee.snapshots[index];
+                let current = bee
+                    .image_at(position, world, snapshot)
+                    .map_err(Outcome::Failed)?;
+                if current.difference(&snapshot.image) >= self.threshold {
+                    break;
+                }
+                reached += 1;
+            }
+            let index = *order.get(reached).ok_or(Outcome::Arrived)?;
+
+            // home in on the active snapshot
+            bee.selection = SnapshotSelection::Fixed(index);
+            bee.approach(world).map_err(Outcome::Failed)
+        });
+
+        bee.position = path[path.len() - 1];
+        bee.selection = selection;
+        RouteTraversal {
+            path,
+            reached,
+            outcome,
+        }
+    }
+}
+
+#[test]
+fn route_test() {
+    use crate::Scene;
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    // a route from the bottom left past the lower landmark towards home
+    let mut bee = scene
+        .bee(&world)
+        .unwrap()
+        .with_snapshot(&world, Vec2::<f32>::new(-4.0, -4.0))
+        .unwrap()
+        .with_snapshot(&world, Vec2::<f32>::new(-2.0, -1.0))
+        .unwrap();
+    bee.position = Vec2::<f32>::new(-5.0, -5.0);
+
+    let traversal = Route::default().follow(&mut bee, &world);
+
+    println!("{:?}", traversal);
+
+    assert_eq!(traversal.outcome, Outcome::Arrived);
+    assert_eq!(traversal.reached, 3);
+    assert!((bee.position - bee.home).len() < 1.0);
+}
+
+#[test]
+fn visual_route_test() {
+    use crate::{Compass, Scene};
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    // the visual compass undoes the heading error by aligning the view with the active snapshot
+    let mut bee = scene
+        .bee(&world)
+        .unwrap()
+        .with_compass(Compass {
+            offset: 30f32.to_radians(),
+            visual: true,
+            ..Compass::default()
+        })
+        .with_snapshot(&world, Vec2::<f32>::new(-4.0, -4.0))
+        .unwrap()
+        .with_snapshot(&world, Vec2::<f32>::new(-2.0, -1.0))
+        .unwrap();
+    bee.position = Vec2::<f32>::new(-5.0, -5.0);
+
+    let traversal = Route::default().follow(&mut bee, &world);
+
+    assert_eq!(traversal.outcome, Outcome::Arrived);
+    assert_eq!(traversal.reached, 3);
+    assert!((bee.position - bee.home).len() < 1.0);
+}
+
+#[test]
+fn route_arena_test() {
+    use crate::{Grid, Scene};
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let mut world = scene.world();
+    let mut bee = scene
+        .bee(&world)
+        .unwrap()
+        .with_snapshot(&world, Vec2::<f32>::new(-4.0, -4.0))
+        .unwrap();
+
+    // the bee stops at the edge of an arena in the corner instead of walking on outside
+    world.grid = Grid::new(-7.0..-5.0, -7.0..-5.0, 1.0);
+    bee.position = Vec2::<f32>::new(-6.0, -6.0);
+    let traversal = Route::default().follow(&mut bee, &world);
+
+    assert_eq!(traversal.outcome, Outcome::LeftArena);
+    assert!(traversal.path.len() < Route::default().max_steps);
+}
@@ -0,0 +1,278 @@
+use std::{fmt, fs, path::Path};
+
+use serde::Deserialize;
+
+use crate::{Bee, Circle, Color, Grid, HomingError, Obstacle, Vec2, World};
+
+/// description of a world, the home of the bee and the output of a run
+/// scenes are stored as toml files, see scenes/default.toml for an example
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    /// obstacles in the world
+    #[serde(default)]
+    pub obstacles: Vec<ObstacleDescription>,
+    /// the grid the bee is allowed to be on
+    pub grid: GridDescription,
+    /// position where the bee takes its snapshot
+    pub home: Vec2<f32>,
+    /// further positions where the bee takes snapshots
+    #[serde(default)]
+    pub snapshots: Vec<Vec2<f32>>,
+    /// path of the rendered image
+    #[serde(default = "default_output")]
+    pub output: String,
+}
+
+/// description of a single obstacle in a scene
+/// the type of the obstacle is selected with the `type` key
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum ObstacleDescription {
+    /// a circular obstacle
+    Circle {
+        /// center of the circle
+        position: Vec2<f32>,
+        /// radius of the circle
+        radius: f32,
+        /// brightness of the circle, black if left out, has to be below white
+        #[serde(default = "default_color")]
+        color: Color,
+    },
+}
+
+/// description of the grid in a scene
+/// the bounds are given as [start, end) pairs
+#[derive(Clone, PartialEq, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GridDescription {
+    /// horizontal bounds of the grid
+    pub width: [f32; 2],
+    /// vertical bounds of the grid
+    pub height: [f32; 2],
+    /// distance between two neighbouring grid positions
+    #[serde(default = "default_spacing")]
+    pub spacing: f32,
+}
+
+/// errors that can occur while loading a scene
+#[derive(Debug)]
+pub enum SceneError {
+    /// the scene file could not be read
+    Io(std::io::Error),
+    /// the scene file is not a valid scene
+    /// the message contains the offending line and field
+    Parse(toml::de::Error),
+    /// the grid of the scene contains no positions
+    /// the message names the offending value
+    InvalidGrid(String),
+    /// an obstacle of the scene cannot be built
+    /// the message names the obstacle and the offending field
+    InvalidObstacle(String),
+}
+
+fn default_output() -> String {
+    "homing.png".to_string()
+}
+
+fn default_color() -> Color {
+    Color::BLACK
+}
+
+fn default_spacing() -> f32 {
+    1.0
+}
+
+impl Scene {
+    /// loads a scene from a toml file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Scene, SceneError> {
+        let source = fs::read_to_string(path).map_err(SceneError::Io)?;
+        Scene::parse(&source)
+    }
+    /// parses a scene from a toml string
+    /// fails if the grid or an obstacle of the scene is invalid,
+    /// see GridDescription::validate and ObstacleDescription::validate
+    pub fn parse(source: &str) -> Result<Scene, SceneError> {
+        let scene: Scene = toml::from_str(source).map_err(SceneError::Parse)?;
+        scene.grid.validate()?;
+        for (i, obstacle) in scene.obstacles.iter().enumerate() {
+            obstacle.validate().map_err(|message| {
+                SceneError::InvalidObstacle(format!("obstacle {}: {}", i + 1, message))
+            })?;
+        }
+        Ok(scene)
+    }
+    /// constructs the world described by the scene
+    pub fn world(&self) -> World {
+        let obstacles = self
+            .obstacles
+            .iter()
+            .map(ObstacleDescription::build)
+            .collect();
+        let grid = Grid::new(
+            self.grid.width[0]..self.grid.width[1],
+            self.grid.height[0]..self.grid.height[1],
+            self.grid.spacing,
+        );
+        World::new(obstacles, grid)
+    }
+    /// constructs a bee that took its snapshots at the home and the snapshot positions of the scene
+    /// the bee uses the home snapshot until it is given another selection, see Bee::with_selection
+    pub fn bee(&self, world: &World) -> Result<Bee, HomingError> {
+        self.snapshots
+            .iter()
+            .try_fold(Bee::new(world, self.home)?, |bee, position| {
+                bee.with_snapshot(world, *position)
+            })
+    }
+}
+
+impl GridDescription {
+    /// checks that the grid contains positions
+    /// the spacing has to be positive and both ranges have to end after they start
+    pub fn validate(&self) -> Result<(), SceneError> {
+        if self.spacing.is_nan() || self.spacing <= 0.0 {
+            return Err(SceneError::InvalidGrid(format!(
+                "spacing has to be positive, got {}",
+                self.spacing
+            )));
+        }
+        for (name, [start, end]) in [("width", self.width), ("height", self.height)] {
+            if start.is_nan() || end.is_nan() || start >= end {
+                return Err(SceneError::InvalidGrid(format!(
+                    "{} has to end after it starts, got [{}, {}]",
+                    name, start, end
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ObstacleDescription {
+    /// checks that the obstacle can be built
+    /// returns a message naming the offending field otherwise
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            ObstacleDescription::Circle { radius, color, .. } => {
+                if radius.is_nan() || radius <= 0.0 {
+                    return Err(format!("radius has to be positive, got {}", radius));
+                }
+                // white is the background, a white obstacle would vanish in the gaps
+                if !(0.0..1.0).contains(&color.0) {
+                    return Err(format!(
+                        "color has to be at least 0.0 and below 1.0 (white), got {}",
+                        color.0
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+    /// turns the description into an obstacle
+    pub fn build(&self) -> Box<dyn Obstacle> {
+        match *self {
+            ObstacleDescription::Circle {
+                position,
+                radius,
+                color,
+            } => Box::new(Circle::new(position, radius).with_color(color)),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneError::Io(err) => write!(f, "could not read scene: {}", err),
+            SceneError::Parse(err) => write!(f, "invalid scene: {}", err),
+            SceneError::InvalidGrid(message) => write!(f, "invalid grid: {}", message),
+            SceneError::InvalidObstacle(message) => write!(f, "invalid obstacle: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io(err) => Some(err),
+            SceneError::Parse(err) => Some(err),
+            SceneError::InvalidGrid(_) | SceneError::InvalidObstacle(_) => None,
+        }
+    }
+}
+
+#[test]
+fn scene_parse_test() {
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+
+    assert_eq!(scene.obstacles.len(), 3);
+    assert_eq!(scene.home, Vec2::<f32>::new(0.0, 0.0));
+
+    let world = scene.world();
+
+    assert_eq!(world.grid, Grid::new(-7.0..8.0, -7.0..8.0, 1.0));
+}
+
+#[test]
+fn scene_error_test() {
+    let source = r#"
+home = [0, 0]
+
+[grid]
+width = [-7, 8]
+height = [-7, 8]
+
+[[obstacles]]
+type = "circle"
+position = [3.5, 2.0]
+"#;
+
+    let message = Scene::parse(source).unwrap_err().to_string();
+
+    println!("{}", message);
+
+    assert!(message.contains("line 8"));
+    assert!(message.contains("radius"));
+
+    // a grid without positions is rejected
+    let source = r#"
+home = [0, 0]
+
+[grid]
+width = [5, 5]
+height = [-7, 8]
+spacing = 0.0
+"#;
+
+    let err = Scene::parse(source).unwrap_err();
+
+    assert!(matches!(err, SceneError::InvalidGrid(_)));
+
+    // so is an obstacle without size
+    let source = r#"
+home = [0, 0]
+
+[grid]
+width = [-7, 8]
+height = [-7, 8]
+
+[[obstacles]]
+type = "circle"
+position = [3.5, 2.0]
+radius = -0.5
+"#;
+
+    let message = Scene::parse(source).unwrap_err().to_string();
+
+    println!("{}", message);
+
+    assert!(message.contains("obstacle 1"));
+    assert!(message.contains("radius"));
+
+    // a white obstacle would be part of the background
+    let source = source.replace("radius = -0.5", "radius = 0.5\ncolor = 1.0");
+    let message = Scene::parse(&source).unwrap_err().to_string();
+
+    assert!(message.contains("color"));
+}
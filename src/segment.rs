@@ -0,0 +1,114 @@
+use serde::Deserialize;
+
+/// brightness of a segment on the image circle
+/// ranges from 0.0 (black) to 1.0 (white)
+/// in scene files a color is written as a number
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct Color(pub f32);
+
+/// datastructure for Segments on the image circle
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Segment {
+    /// bisector for the Segment, radians
+    /// ranges from 0..2Pi
+    pub bisector: f32,
+    /// width of the Segment, radians
+    pub width: f32,
+    /// color of the Segment
+    pub color: Color,
+}
+
+/// trait for the signed angular distance between two values on the image circle
+pub trait Distance {
+    /// returns the signed distance from self to other
+    /// the result ranges from -Pi..Pi
+    fn dist(&self, other: Self) -> f32;
+}
+
+impl Distance for f32 {
+    fn dist(&self, other: Self) -> f32 {
+        (other - self).sin().atan2((other - self).cos())
+    }
+}
+
+impl Distance for Segment {
+    fn dist(&self, other: Self) -> f32 {
+        self.bisector.dist(other.bisector)
+    }
+}
+
+impl Color {
+    /// color of the landmarks in the paper
+    pub const BLACK: Color = Color(0.0);
+    /// color of the background between the landmarks
+    pub const WHITE: Color = Color(1.0);
+
+    /// whether a segment of this color is a landmark
+    /// the background between the landmarks is white, see Image::new,
+    /// every other color belongs to a landmark
+    pub fn is_landmark(&self) -> bool {
+        *self != Color::WHITE
+    }
+    /// absolute difference in brightness between two colors
+    pub fn difference(&self, other: Color) -> f32 {
+        (self.0 - other.0).abs()
+    }
+}
+
+impl Segment {
+    /// checks if two segments collide/overlap
+    /// returns true if the two segments collide/overlap
+    #[deprecated(
+        note = "Image::new resolves occlusion by distance and no longer merges colliding segments"
+    )]
+    pub fn collides(&self, other: Segment) -> bool {
+        // calculate the distance between both bisectors
+        let arc_dist = self.dist(other).abs();
+        // if the arc_dist is greater than the sum of the halves of either width there is no intersection/overlap
+        arc_dist <= self.width / 2.0 + other.width / 2.0
+    }
+}
+
+#[test]
+#[allow(deprecated)]
+fn segment_collide_test() {
+    use std::f32::consts::PI;
+
+    let s1 = Segment {
+        bisector: PI / 4.0,
+        width: PI / 2.0,
+        color: Color::BLACK,
+    };
+    let s2 = Segment {
+        bisector: 5.0 * PI / 4.0,
+        width: PI / 2.0,
+        color: Color::BLACK,
+    };
+    let s3 = Segment {
+        bisector: 7.0 * PI / 4.0,
+        width: PI / 2.0,
+        color: Color::BLACK,
+    };
+
+    assert!(!s1.collides(s2));
+    assert!(s1.collides(s3));
+}
+
+#[test]
+fn i_dont_know_what_im_doing() {
+    let s1 = Segment {
+        bisector: 0.5,
+        width: 1.0,
+        color: Color::BLACK,
+    };
+    let s2 = Segment {
+        bisector: 0.3,
+        width: 1.0,
+        color: Color::BLACK,
+    };
+
+    let test = s1.dist(s2);
+
+    assert!(test > 0.0)
+}
@@ -0,0 +1,137 @@
+use std::str::FromStr;
+
+use crate::{Image, Vec2};
+
+/// an image the bee remembers together with the position it was taken at
+#[derive(Clone, PartialEq, PartialOrd, Debug)]
+pub struct Snapshot {
+    /// position where the snapshot was taken
+    pub position: Vec2<f32>,
+    /// the image seen at that position
+    pub image: Image,
+    /// offset from the position of the snapshot to home, zero for the snapshot taken at home
+    pub home_vector: Vec2<f32>,
+}
+
+/// image difference below which a snapshot is recognized when selecting by lowest difference
+const RECOGNITION_THRESHOLD: f32 = 0.25;
+
+/// policy for choosing the snapshot that drives the homing vector
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum SnapshotSelection {
+    /// always use the snapshot taken at home
+    Home,
+    /// use the snapshot that differs least from the current image
+    /// the image difference grows quickly away from a snapshot, so the other snapshots
+    /// are only used where they differ less than the threshold and the home snapshot elsewhere
+    LowestDifference {
+        /// image difference below which a snapshot other than the home snapshot is recognized
+        threshold: f32,
+    },
+    /// always use the snapshot with the given index, e.g. the active one of a route
+    Fixed(usize),
+}
+
+impl Snapshot {
+    /// takes a snapshot of the given image at the position, remembering where home lies from there
+    pub fn new(position: Vec2<f32>, image: Image, home: Vec2<f32>) -> Snapshot {
+        Snapshot {
+            position,
+            image,
+            home_vector: home - position,
+        }
+    }
+    /// turns a vector towards the position of the snapshot into a vector towards home
+    /// both the vector towards the snapshot and the home vector are taken with a length of one,
+    /// so the bee heads home while the model keeps it close to where the snapshot was taken
+    pub fn guide(&self, vec: Vec2<f32>) -> Vec2<f32> {
+        if self.home_vector.is_empty() {
+            return vec;
+        }
+        if vec.is_empty() || !vec.len().is_finite() {
+            return self.home_vector.normalized();
+        }
+        vec.normalized() + self.home_vector.normalized()
+    }
+}
+
+impl SnapshotSelection {
+    /// chooses one of the snapshots for the current image, the first one is taken at home
+    /// the current image is given as it is seen when compared with each snapshot
+    /// returns None if there are no snapshots
+    pub fn select<'a>(
+        &self,
+        snapshots: &'a [Snapshot],
+        current: impl Fn(&Snapshot) -> Image,
+    ) -> Option<&'a Snapshot> {
+        match self {
+            SnapshotSelection::Home => snapshots.first(),
+            SnapshotSelection::LowestDifference { threshold } => snapshots
+                .iter()
+                .map(|snapshot| (snapshot, snapshot.image.difference(&current(snapshot))))
+                .enumerate()
+                .filter(|(i, (_, difference))| *i == 0 || difference < threshold)
+                .map(|(_, pair)| pair)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(snapshot, _)| snapshot),
+            SnapshotSelection::Fixed(index) => snapshots.get(*index),
+        }
+    }
+}
+
+impl FromStr for SnapshotSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "home" => Ok(SnapshotSelection::Home),
+            "lowest-difference" => Ok(SnapshotSelection::LowestDifference {
+                threshold: RECOGNITION_THRESHOLD,
+            }),
+            _ => Err(format!(
+                "unknown snapshot selection `{}`, expected `home` or `lowest-difference`",
+                s
+            )),
+        }
+    }
+}
+
+#[test]
+fn selection_test() {
+    use crate::Scene;
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+
+    let snapshots: Vec<Snapshot> = [(0.0, 0.0), (5.0, -5.0)]
+        .into_iter()
+        .map(|(x, y)| {
+            let position = Vec2::<f32>::new(x, y);
+            let image = Image::new(position, &world.obstacles).unwrap();
+            Snapshot::new(position, image, Vec2::<f32>::new(0.0, 0.0))
+        })
+        .collect();
+
+    let current = Image::new(Vec2::<f32>::new(4.9, -5.0), &world.obstacles).unwrap();
+
+    let home = SnapshotSelection::Home
+        .select(&snapshots, |_| current.clone())
+        .unwrap();
+    let lowest_difference = SnapshotSelection::LowestDifference {
+        threshold: RECOGNITION_THRESHOLD,
+    };
+    let closest = lowest_difference
+        .select(&snapshots, |_| current.clone())
+        .unwrap();
+
+    assert_eq!(home.position, Vec2::<f32>::new(0.0, 0.0));
+    assert_eq!(closest.position, Vec2::<f32>::new(5.0, -5.0));
+
+    // farther away the snapshot is no longer recognized
+    let current = Image::new(Vec2::<f32>::new(2.0, -5.0), &world.obstacles).unwrap();
+    let fallback = lowest_difference
+        .select(&snapshots, |_| current.clone())
+        .unwrap();
+
+    assert_eq!(fallback.position, Vec2::<f32>::new(0.0, 0.0));
+}
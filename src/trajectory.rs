@@ -0,0 +1,176 @@
+use std::fmt;
+
+use crate::{Bee, HomingError, Vec2, VectorField, World};
+
+/// settings for moving a bee along its homing vectors
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct Simulation {
+    /// distance the bee moves in one step
+    pub step_length: f32,
+    /// maximum number of steps
+    pub max_steps: usize,
+    /// distance to home below which the bee has arrived
+    pub arrival_radius: f32,
+}
+
+/// why a trajectory ended
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Outcome {
+    /// the bee got closer to its home than the arrival radius or recognized the last snapshot of a route
+    Arrived,
+    /// the bee came back to a position it already visited or stopped moving
+    Loop,
+    /// the bee left the area of the grid
+    LeftArena,
+    /// the bee moved into an obstacle
+    EnteredObstacle,
+    /// the bee used up all its steps
+    MaxSteps,
+    /// the bee could not calculate a homing vector
+    Failed(HomingError),
+    /// the streamline reached a part of the field without vectors to follow
+    NoVector,
+}
+
+/// the path of a bee and why it ended
+#[derive(Clone, PartialEq, Debug)]
+pub struct Trajectory {
+    /// every position of the bee, starting at the start position
+    pub path: Vec<Vec2<f32>>,
+    /// why the bee stopped
+    pub outcome: Outcome,
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Simulation {
+            step_length: 0.5,
+            max_steps: 100,
+            arrival_radius: 0.25,
+        }
+    }
+}
+
+impl Simulation {
+    /// moves the bee from the start position along its homing vectors until it stops
+    /// the bee is left at the last position of the path
+    pub fn run(&self, bee: &mut Bee, world: &World, start: Vec2<f32>) -> Trajectory {
+        let home = bee.home;
+        let mut path = vec![start];
+        let outcome = self.walk(world, home, &mut path, |position| {
+            bee.position = position;
+            bee.home(world).map_err(Outcome::Failed)
+        });
+        bee.position = path[path.len() - 1];
+        Trajectory { path, outcome }
+    }
+    /// follows the vectors of the field from the start position until the streamline stops
+    /// the vectors between the grid positions are interpolated,
+    /// the streamline ends with Outcome::NoVector where there are no vectors to interpolate
+    pub fn streamline(&self, field: &VectorField, world: &World, start: Vec2<f32>) -> Trajectory {
+        let mut path = vec![start];
+        let outcome = self.walk(world, field.home, &mut path, |position| {
+            field.interpolate(position).ok_or(Outcome::NoVector)
+        });
+        Trajectory { path, outcome }
+    }
+    /// takes the steps along the given vectors and records them in the path
+    /// a failing vector ends the path with the outcome it fails with
+    pub(crate) fn walk(
+        &self,
+        world: &World,
+        home: Vec2<f32>,
+        path: &mut Vec<Vec2<f32>>,
+        mut vector: impl FnMut(Vec2<f32>) -> Result<Vec2<f32>, Outcome>,
+    ) -> Outcome {
+        let mut position = path[path.len() - 1];
+        for _ in 0..self.max_steps {
+            if (home - position).len() < self.arrival_radius {
+                return Outcome::Arrived;
+            }
+
+            // move along the homing vector
+            let vec = match vector(position) {
+                Ok(vec) => vec,
+                Err(outcome) => return outcome,
+            };
+            if vec.len() == 0.0 || !vec.len().is_finite() {
+                return Outcome::Loop;
+            }
+            position += self.step_length * vec.normalized();
+            path.push(position);
+
+            if world.obstacles.iter().any(|o| o.contains(position)) {
+                return Outcome::EnteredObstacle;
+            }
+            if !world.grid.contains(position) {
+                return Outcome::LeftArena;
+            }
+            // the bee is back where it was before the previous step
+            let earlier = &path[..path.len().saturating_sub(2)];
+            if earlier
+                .iter()
+                .any(|p| (*p - position).len() < self.step_length / 2.0)
+            {
+                return Outcome::Loop;
+            }
+        }
+        if (home - position).len() < self.arrival_radius {
+            Outcome::Arrived
+        } else {
+            Outcome::MaxSteps
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Arrived => write!(f, "arrived at home"),
+            Outcome::Loop => write!(f, "stuck in a loop"),
+            Outcome::LeftArena => write!(f, "left the arena"),
+            Outcome::EnteredObstacle => write!(f, "entered an obstacle"),
+            Outcome::MaxSteps => write!(f, "ran out of steps"),
+            Outcome::Failed(err) => write!(f, "failed: {}", err),
+            Outcome::NoVector => write!(f, "found no vector to follow"),
+        }
+    }
+}
+
+#[test]
+fn trajectory_test() {
+    use crate::{Grid, Scene};
+
+    let scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    let world = scene.world();
+    let mut bee = scene.bee(&world).unwrap();
+
+    let simulation = Simulation::default();
+
+    let home = simulation.run(&mut bee, &world, Vec2::<f32>::new(-3.0, 3.0));
+
+    println!("{:?}", home);
+
+    assert_eq!(home.outcome, Outcome::Arrived);
+    assert!((bee.position - bee.home).len() < simulation.arrival_radius);
+
+    // the streamline of the field leads home from the same start
+    let field = VectorField::generate(scene.bee(&world).unwrap(), &world);
+    let streamline = simulation.streamline(&field, &world, Vec2::<f32>::new(-3.0, 3.0));
+
+    assert_eq!(streamline.outcome, Outcome::Arrived);
+
+    // a field without vectors leaves the streamline nothing to follow
+    let mut empty = field.clone();
+    empty.vectors = vec![vec![None; empty.grid.rows()]; empty.grid.columns()];
+    let stopped = simulation.streamline(&empty, &world, Vec2::<f32>::new(-3.0, 3.0));
+
+    assert_eq!(stopped.outcome, Outcome::NoVector);
+
+    // an arena in the corner that the bee leaves on its way home
+    let mut corner = scene.world();
+    corner.grid = Grid::new(-7.0..-5.0, -7.0..-5.0, 1.0);
+    let out = simulation.run(&mut bee, &corner, Vec2::<f32>::new(-6.0, -6.0));
+
+    assert_eq!(out.outcome, Outcome::LeftArena);
+}
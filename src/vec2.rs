@@ -0,0 +1,103 @@
+use std::ops::{Add, AddAssign, Index, Mul, Sub};
+
+use serde::Deserialize;
+
+/// datastructure for 2d vectors
+/// in scene files a vector is written as an array: [x, y]
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug, Deserialize)]
+#[serde(transparent)]
+pub struct Vec2<T> {
+    /// data for the 2d vector
+    pub data: [T; 2],
+}
+
+impl<T> Vec2<T>
+where
+    Vec2<T>: Into<Vec2<f32>>,
+    Vec2<T>: Clone,
+{
+    /// returns the euclidean length of the vector
+    pub fn len(&self) -> f32 {
+        let vec: Vec2<f32> = (*self).clone().into();
+        (vec[0] * vec[0] + vec[1] * vec[1]).sqrt()
+    }
+    /// returns true if the vector has a length of zero
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0.0
+    }
+    /// returns a vector with the same direction and a length of one
+    pub fn normalized(&self) -> Vec2<f32> {
+        let vec: Vec2<f32> = (*self).clone().into();
+        let len = vec.len();
+        Vec2::<f32>::new(vec[0] / len, vec[1] / len)
+    }
+}
+impl<T> Vec2<T> {
+    /// creates a new vector from its x and y components
+    pub fn new(x: T, y: T) -> Vec2<T> {
+        Vec2 { data: [x, y] }
+    }
+}
+
+impl<T> Index<usize> for Vec2<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl From<Vec2<i32>> for Vec2<f32> {
+    fn from(vec: Vec2<i32>) -> Vec2<f32> {
+        Vec2::<f32> {
+            data: [vec.data[0] as f32, vec.data[1] as f32],
+        }
+    }
+}
+
+impl<T> Sub for Vec2<T>
+where
+    T: Sub<Output = T>,
+    T: Copy,
+{
+    type Output = Vec2<T>;
+
+    fn sub(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2::<T> {
+            data: [self[0] - rhs[0], self[1] - rhs[1]],
+        }
+    }
+}
+
+impl<T> Add for Vec2<T>
+where
+    T: Add<Output = T>,
+    T: Copy,
+{
+    type Output = Vec2<T>;
+
+    fn add(self, rhs: Vec2<T>) -> Self::Output {
+        Vec2::<T> {
+            data: [self[0] + rhs[0], self[1] + rhs[1]],
+        }
+    }
+}
+
+impl<T> AddAssign for Vec2<T>
+where
+    T: Add<Output = T>,
+    T: Copy,
+{
+    fn add_assign(&mut self, rhs: Self) {
+        self.data[0] = self[0] + rhs[0];
+        self.data[1] = self[1] + rhs[1];
+    }
+}
+
+impl Mul<Vec2<f32>> for f32 {
+    type Output = Vec2<f32>;
+
+    fn mul(self, rhs: Vec2<f32>) -> Self::Output {
+        Vec2::<f32>::new(self * rhs[0], self * rhs[1])
+    }
+}
@@ -0,0 +1,456 @@
+use std::f32::consts::PI;
+
+use crate::{Bee, Grid, Outcome, Shape, Trajectory, Vec2, World};
+
+/// VectorField struct for storing all generated vectors
+#[derive(Clone, PartialEq, Debug)]
+pub struct VectorField {
+    /// the grid the vectors were sampled on
+    pub grid: Grid,
+    /// the homing vectors, indexed by VectorField::index
+    /// positions where no homing vector could be calculated hold None
+    pub vectors: Vec<Vec<Option<Vec2<f32>>>>,
+    /// number of snapshot segments the model left unmatched, indexed like the vectors
+    /// positions where the segments could not be matched hold None
+    pub unmatched: Vec<Vec<Option<usize>>>,
+    /// average angle between the homing vectors and the correct direction, radians
+    /// only positions with a homing vector count, home itself is left out
+    /// None if there is no such position
+    pub avg_angular_error: Option<f32>,
+    /// outlines of the obstacles of the world the field was generated in
+    pub shapes: Vec<Shape>,
+    /// home of the bee the field was generated for
+    pub home: Vec2<f32>,
+    /// simulated paths of the bee that are drawn over the field
+    pub trajectories: Vec<Trajectory>,
+    /// streamlines of the field that are drawn over the field
+    pub streamlines: Vec<Trajectory>,
+}
+
+impl VectorField {
+    /// generates the homing vector for every position on the grid of the world
+    /// positions where the bee cant calculate a homing vector are left empty
+    pub fn generate(mut bee: Bee, world: &World) -> VectorField {
+        // clone the world grid
+        let grid = world.grid.clone();
+        // generate the data storage for the vectors
+        // the outer list is indexed by x and the inner one by y, see VectorField::index
+        let mut field = vec![vec![None; grid.rows()]; grid.columns()];
+        let mut unmatched = vec![vec![None; grid.rows()]; grid.columns()];
+
+        let mut out = VectorField {
+            grid,
+            vectors: vec![],
+            unmatched: vec![],
+            avg_angular_error: None,
+            shapes: world.obstacles.iter().map(|o| o.shape()).collect(),
+            home: bee.home,
+            trajectories: vec![],
+            streamlines: vec![],
+        };
+
+        // sum of the angular errors and the number of positions they were measured at
+        let mut error_sum = 0.0;
+        let mut error_count = 0;
+
+        for position in out.grid.positions() {
+            // calculate indices for storing
+            let index = out.index(position);
+            // position the bee correctly
+            bee.position = position;
+            // count the segments that were left out
+            unmatched[index[0]][index[1]] = bee.unmatched(world).ok();
+            // generate the homing vector
+            // if there is none the position stays empty
+            let Ok(homing_vector) = bee.home(world) else {
+                continue;
+            };
+
+            // calculate the angular error of the generated vector
+            // the correct vector points from the bee to its home
+            let correct = bee.home - bee.position;
+            let dot = correct[0] * homing_vector[0] + correct[1] * homing_vector[1];
+            let angle = (dot / (correct.len() * homing_vector.len())).acos();
+
+            // only positions with a defined angle count towards the average,
+            // there is none at home or where the vector has no length
+            if angle.is_finite() {
+                error_sum += angle;
+                error_count += 1;
+            }
+
+            // store the homing vector
+            field[index[0]][index[1]] = Some(homing_vector);
+        }
+
+        if error_count > 0 {
+            out.avg_angular_error = Some(error_sum / error_count as f32);
+        }
+        out.vectors = field;
+        out.unmatched = unmatched;
+        out
+    }
+    /// adds simulated paths of the bee that are drawn over the field
+    pub fn with_trajectories(mut self, trajectories: Vec<Trajectory>) -> VectorField {
+        self.trajectories = trajectories;
+        self
+    }
+    /// adds streamlines that are drawn over the field
+    pub fn with_streamlines(mut self, streamlines: Vec<Trajectory>) -> VectorField {
+        self.streamlines = streamlines;
+        self
+    }
+    /// direction of the field at any position inside the grid
+    /// the normalized vectors of the surrounding grid positions are interpolated bilinearly,
+    /// grid positions without a vector and the home are left out,
+    /// the vector at home only points wherever rounding errors lead it
+    /// returns None outside the grid or if none of the surrounding grid positions has a vector
+    pub fn interpolate(&self, position: Vec2<f32>) -> Option<Vec2<f32>> {
+        if !self.grid.contains(position) {
+            return None;
+        }
+        // position in units of grid positions, clamped onto the outermost grid positions
+        let x = ((position[0] - self.grid.width.start) / self.grid.spacing)
+            .clamp(0.0, (self.grid.columns() - 1) as f32);
+        let y = ((position[1] - self.grid.height.start) / self.grid.spacing)
+            .clamp(0.0, (self.grid.rows() - 1) as f32);
+        let (column, row) = (x.floor() as usize, y.floor() as usize);
+        let (fx, fy) = (x - column as f32, y - row as f32);
+
+        let mut sum = Vec2::<f32>::new(0.0, 0.0);
+        let mut total = 0.0;
+        for (dx, dy, weight) in [
+            (0, 0, (1.0 - fx) * (1.0 - fy)),
+            (1, 0, fx * (1.0 - fy)),
+            (0, 1, (1.0 - fx) * fy),
+            (1, 1, fx * fy),
+        ] {
+            let (column, row) = (column + dx, row + dy);
+            if weight <= 0.0 || column >= self.grid.columns() || row >= self.grid.rows() {
+                continue;
+            }
+            let corner = self.grid.position(column, row);
+            if (self.home - corner).len() < self.grid.spacing / 2.0 {
+                continue;
+            }
+            let index = self.index(corner);
+            if let Some(vec) = self.vectors[index[0]][index[1]] {
+                if vec.len() > 0.0 && vec.len().is_finite() {
+                    sum += weight * vec.normalized();
+                    total += weight;
+                }
+            }
+        }
+        (total > 0.0).then(|| (1.0 / total) * sum)
+    }
+    /// renders the vector field into a png image at the given path
+    /// size is the size of the image in pixels: (width, height)
+    /// the plot is scaled to fit the grid into the image, the label is placed below the plot
+    /// trajectories and streamlines are drawn on top, colored by their outcome
+    /// fails if the grid has no positions
+    pub fn draw(&self, path: &str, size: (u32, u32)) -> Result<(), Box<dyn std::error::Error>> {
+        use plotters::coord::types::RangedCoordf32;
+        use plotters::prelude::*;
+        use plotters::style::text_anchor::{HPos, Pos, VPos};
+
+        if self.grid.columns() == 0 || self.grid.rows() == 0 {
+            return Err("cannot draw a vector field without grid positions".into());
+        }
+
+        // the extents of the plot in world coordinates
+        // every grid position gets half the spacing on each side
+        let spacing = self.grid.spacing;
+        let last = self
+            .grid
+            .position(self.grid.columns() - 1, self.grid.rows() - 1);
+        let x_range = (
+            self.grid.width.start - spacing / 2.0,
+            last[0] + spacing / 2.0,
+        );
+        let y_range = (
+            self.grid.height.start - spacing / 2.0,
+            last[1] + spacing / 2.0,
+        );
+
+        // reserve space for the label below the plot
+        let font_size = size.0 as f32 / 30.0;
+        let label_height = (font_size * 3.0) as u32;
+        let plot_height = size.1.saturating_sub(label_height);
+
+        // pixels per unit, the same for both axes so the plot isnt squished
+        let scale = (size.0 as f32 / (x_range.1 - x_range.0))
+            .min(plot_height as f32 / (y_range.1 - y_range.0));
+        let plot_size = (
+            ((x_range.1 - x_range.0) * scale) as i32,
+            ((y_range.1 - y_range.0) * scale) as i32,
+        );
+        // center the plot horizontally
+        let offset = ((size.0 as i32 - plot_size.0) / 2, 0);
+
+        let canvas = BitMapBackend::new(path, size).into_drawing_area();
+
+        canvas.fill(&RGBColor(240, 240, 240))?;
+
+        let root =
+            canvas
+                .clone()
+                .apply_coord_spec(Cartesian2d::<RangedCoordf32, RangedCoordf32>::new(
+                    x_range.0..x_range.1,
+                    y_range.1..y_range.0,
+                    (
+                        offset.0..offset.0 + plot_size.0,
+                        offset.1..offset.1 + plot_size.1,
+                    ),
+                ));
+
+        // the arrow is 34 units long and should span 80% of the grid spacing
+        let arrow_scale = 0.8 * spacing * scale / 34.0;
+        let vector = |x: f32, y: f32, vec: Vec2<f32>| {
+            let angle = vec[1].atan2(vec[0]);
+            let arrow = [
+                (-17, -1),
+                (6, -1),
+                (5, -3),
+                (17, 0),
+                (5, 3),
+                (6, 1),
+                (-17, 1),
+            ];
+            let rotated = arrow
+                .iter()
+                .map(|(x, y)| {
+                    let x = *x as f32 * arrow_scale;
+                    let y = *y as f32 * arrow_scale;
+                    let new_x = (x * angle.cos()) - (y * angle.sin());
+                    let new_y = -((y * angle.cos()) + (x * angle.sin()));
+                    (new_x.round() as i32, new_y.round() as i32)
+                })
+                .collect::<Vec<_>>();
+            EmptyElement::at((x, y)) + Polygon::new(rotated, ShapeStyle::from(&BLACK).filled())
+        };
+
+        // draw the obstacles of the world
+        for shape in &self.shapes {
+            match *shape {
+                Shape::Circle {
+                    center,
+                    radius,
+                    color,
+                } => {
+                    // approximate the circle with a polygon so the radius is in world coordinates
+                    let outline = (0..64)
+                        .map(|i| {
+                            let angle = i as f32 / 64.0 * 2.0 * PI;
+                            (
+                                center[0] + radius * angle.cos(),
+                                center[1] + radius * angle.sin(),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let gray = (color.0.clamp(0.0, 1.0) * 255.0) as u8;
+                    root.draw(&Polygon::new(
+                        outline,
+                        ShapeStyle::from(&RGBColor(gray, gray, gray)).filled(),
+                    ))?;
+                }
+            }
+        }
+
+        canvas.draw(&Text::new(
+            format!(
+                "average angular error: {}",
+                self.avg_angular_error
+                    .map_or("n/a".to_string(), |error| format!(
+                        "{}°",
+                        error * 180.0 / PI
+                    ))
+            ),
+            (size.0 as i32 / 2, plot_size.1 + label_height as i32 / 2),
+            ("sans-serif", font_size)
+                .into_font()
+                .into_text_style(&canvas)
+                .pos(Pos::new(HPos::Center, VPos::Center)),
+        ))?;
+
+        for position in self.grid.positions() {
+            // leave out the vector on the home, the cross is drawn there
+            if (self.home - position).len() < spacing / 2.0 {
+                continue;
+            }
+            let index = self.index(position);
+            if let Some(vec) = self.vectors[index[0]][index[1]] {
+                root.draw(&vector(position[0], position[1], vec))?;
+            }
+        }
+        // the color of a path tells why it ended
+        let outcome_color = |outcome: &Outcome| match outcome {
+            Outcome::Arrived => RGBColor(0, 150, 0),
+            Outcome::Loop => RGBColor(230, 120, 0),
+            Outcome::LeftArena => RGBColor(0, 90, 220),
+            Outcome::EnteredObstacle => RGBColor(200, 0, 0),
+            Outcome::MaxSteps => RGBColor(150, 0, 150),
+            Outcome::Failed(_) => RGBColor(120, 120, 120),
+            Outcome::NoVector => RGBColor(140, 90, 40),
+        };
+        // streamlines are drawn thinner than the simulated paths
+        let lines = self
+            .streamlines
+            .iter()
+            .map(|line| (line, 0.03))
+            .chain(self.trajectories.iter().map(|line| (line, 0.06)));
+        for (line, width) in lines {
+            let color = outcome_color(&line.outcome);
+            let points = line.path.iter().map(|p| (p[0], p[1])).collect::<Vec<_>>();
+            root.draw(&PathElement::new(
+                points,
+                ShapeStyle::from(&color).stroke_width((width * spacing * scale).max(1.0) as u32),
+            ))?;
+            // mark the start of the path
+            root.draw(&Circle::new(
+                (line.path[0][0], line.path[0][1]),
+                (0.08 * spacing * scale).max(2.0) as i32,
+                ShapeStyle::from(&color).filled(),
+            ))?;
+        }
+
+        root.draw(&Cross::new(
+            (self.home[0], self.home[1]),
+            (0.25 * spacing * scale) as i32,
+            ShapeStyle::from(&BLACK).stroke_width((0.07 * spacing * scale).max(1.0) as u32),
+        ))?;
+        root.present()?;
+        Ok(())
+    }
+    /// writes the vector field as csv into the file at the given path
+    /// every line holds the position, the homing vector and the number of unmatched segments:
+    /// x,y,dx,dy,unmatched
+    /// the values are left empty where there are none
+    pub fn save(&self, path: &str) -> std::io::Result<()> {
+        use std::io::Write;
+
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(file, "x,y,dx,dy,unmatched")?;
+        for position in self.grid.positions() {
+            let index = self.index(position);
+            match self.vectors[index[0]][index[1]] {
+                Some(vec) => write!(
+                    file,
+                    "{},{},{},{},",
+                    position[0], position[1], vec[0], vec[1]
+                )?,
+                None => write!(file, "{},{},,,", position[0], position[1])?,
+            }
+            match self.unmatched[index[0]][index[1]] {
+                Some(count) => writeln!(file, "{}", count)?,
+                None => writeln!(file)?,
+            }
+        }
+        file.flush()
+    }
+    /// turns a position on the grid into the indices of VectorField::vectors
+    /// positions between grid points are rounded to the closest one
+    pub fn index(&self, position: Vec2<f32>) -> Vec2<usize> {
+        self.grid.index(position)
+    }
+}
+
+/// this test will always pass if the program doesnt crash
+#[test]
+fn vec_field_test() {
+    let vec_q1 = Vec2::<f32>::new(-1.0, -1.0).normalized();
+    let vec_q2 = Vec2::<f32>::new(1.0, -1.0).normalized();
+    let vec_q3 = Vec2::<f32>::new(1.0, 1.0).normalized();
+    let vec_q4 = Vec2::<f32>::new(-1.0, 1.0).normalized();
+
+    let pos_q1 = Vec2::<f32>::new(7.0, 7.0);
+    let pos_q2 = Vec2::<f32>::new(-6.0, 6.0);
+    let pos_q3 = Vec2::<f32>::new(-5.0, -5.0);
+    let pos_q4 = Vec2::<f32>::new(4.0, -4.0);
+
+    let grid = Grid {
+        width: -7.0..8.0,
+        height: -7.0..8.0,
+        spacing: 1.0,
+    };
+
+    let field = vec![vec![Some(Vec2::<f32>::new(0.0, 0.0)); grid.rows()]; grid.columns()];
+    let unmatched = vec![vec![Some(0); grid.rows()]; grid.columns()];
+
+    let mut vector_field = VectorField {
+        grid,
+        vectors: field,
+        unmatched,
+        avg_angular_error: None,
+        shapes: vec![],
+        home: Vec2::<f32>::new(0.0, 0.0),
+        trajectories: vec![],
+        streamlines: vec![],
+    };
+
+    let index_1 = vector_field.index(pos_q1);
+    let index_2 = vector_field.index(pos_q2);
+    let index_3 = vector_field.index(pos_q3);
+    let index_4 = vector_field.index(pos_q4);
+
+    vector_field.vectors[index_1[0]][index_1[1]] = Some(vec_q1);
+    vector_field.vectors[index_2[0]][index_2[1]] = Some(vec_q2);
+    vector_field.vectors[index_3[0]][index_3[1]] = Some(vec_q3);
+    vector_field.vectors[index_4[0]][index_4[1]] = Some(vec_q4);
+
+    vector_field.trajectories = vec![Trajectory {
+        path: vec![pos_q3, Vec2::<f32>::new(-4.5, -4.0), pos_q4],
+        outcome: Outcome::Loop,
+    }];
+
+    vector_field.draw("test.png", (640, 740)).unwrap();
+}
+
+#[test]
+fn off_center_home_test() {
+    use crate::Scene;
+
+    let mut scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    scene.home = Vec2::<f32>::new(2.0, 1.0);
+    scene.grid.width = [-1.0, 5.0];
+    scene.grid.height = [-2.0, 4.0];
+    let world = scene.world();
+    let field = VectorField::generate(scene.bee(&world).unwrap(), &world);
+
+    assert_eq!(field.home, scene.home);
+    // there is a vector at home, but no direction it should point in
+    let home = field.index(field.home);
+    assert!(field.vectors[home[0]][home[1]].is_some());
+
+    // the average over every position but home of the angle to the direction towards a target
+    let average_error = |target: Vec2<f32>| {
+        let angles: Vec<f32> = field
+            .grid
+            .positions()
+            .filter(|&position| position != field.home)
+            .filter_map(|position| {
+                let index = field.index(position);
+                let vec = field.vectors[index[0]][index[1]]?;
+                let correct = target - position;
+                let dot = correct[0] * vec[0] + correct[1] * vec[1];
+                Some((dot / (correct.len() * vec.len())).acos())
+            })
+            .filter(|angle| angle.is_finite())
+            .collect();
+        angles.iter().sum::<f32>() / angles.len() as f32
+    };
+
+    let avg_angular_error = field.avg_angular_error.unwrap();
+
+    println!("{}", avg_angular_error);
+
+    assert!((avg_angular_error - average_error(scene.home)).abs() < 1e-4);
+    assert!((avg_angular_error - average_error(Vec2::<f32>::new(0.0, 0.0))).abs() > 0.1);
+
+    // without any position other than home there is nothing to average
+    scene.grid.width = [2.0, 3.0];
+    scene.grid.height = [1.0, 2.0];
+    let world = scene.world();
+    let field = VectorField::generate(scene.bee(&world).unwrap(), &world);
+
+    assert_eq!(field.avg_angular_error, None);
+}
@@ -0,0 +1,108 @@
+use std::f32::consts::PI;
+
+use crate::{HomingError, HomingModel, Image, Retina, Vec2, View};
+
+/// the image warping model by Franz et al.
+/// assumes that all landmarks are equally far away from the home,
+/// tries every home direction, rotation and distance, warps the snapshot into the image
+/// the bee would see after that movement and keeps the one closest to the current image
+/// the rotations are tried in steps of one ommatidium
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub struct WarpingModel {
+    /// the retina the images are compared on
+    pub retina: Retina,
+    /// number of home directions that are tried
+    pub directions: usize,
+    /// number of distances that are tried, relative to the distance of the landmarks
+    pub distances: usize,
+}
+
+impl Default for WarpingModel {
+    /// a retina with an ommatidium every 5 degrees and a direction every 5 degrees
+    fn default() -> Self {
+        WarpingModel {
+            retina: Retina {
+                ommatidia: 72,
+                acceptance_angle: 5f32.to_radians(),
+                threshold: 0.5,
+            },
+            directions: 72,
+            distances: 10,
+        }
+    }
+}
+
+impl HomingModel for WarpingModel {
+    /// the homing vector points in the best home direction,
+    /// its length is the distance to home relative to the distance of the landmarks
+    fn home(&self, snapshot: &Image, view: &View) -> Result<Vec2<f32>, HomingError> {
+        let snapshot = self.retina.project(snapshot);
+        let current = self.retina.project(&view.current);
+        let n = snapshot.pixels.len();
+        let spacing = snapshot.spacing();
+
+        // (mismatch, direction, distance) of the best movement so far
+        let mut best = (f32::INFINITY, 0.0, 0.0);
+        for d in 0..self.distances {
+            // distances from 0 up to just below the distance of the landmarks
+            let distance = d as f32 / self.distances as f32;
+            for a in 0..self.directions {
+                let direction = a as f32 / self.directions as f32 * 2.0 * PI;
+                // where the landmark seen by every snapshot ommatidium appears after the movement
+                // the bee stands at -distance in the home direction, the landmarks at distance 1
+                // stored as the ommatidium before that direction and the fraction to the next one
+                let warped: Vec<(usize, f32)> = (0..n)
+                    .map(|i| {
+                        let angle = i as f32 * spacing;
+                        let warped_angle = (angle.sin() + distance * direction.sin())
+                            .atan2(angle.cos() + distance * direction.cos());
+                        let position = warped_angle.rem_euclid(2.0 * PI) / spacing;
+                        (position.floor() as usize % n, position - position.floor())
+                    })
+                    .collect();
+                for rotation in 0..n {
+                    let mismatch: f32 = warped
+                        .iter()
+                        .zip(&snapshot.pixels)
+                        .map(|(&(index, fraction), brightness)| {
+                            let index = index + rotation;
+                            let seen = (1.0 - fraction) * current.pixels[index % n]
+                                + fraction * current.pixels[(index + 1) % n];
+                            (seen - brightness).powi(2)
+                        })
+                        .sum();
+                    if mismatch < best.0 {
+                        best = (mismatch, direction, distance);
+                    }
+                }
+            }
+        }
+
+        let (_, direction, distance) = best;
+        Ok(distance * Vec2::<f32>::new(direction.cos(), direction.sin()))
+    }
+}
+
+#[test]
+fn warping_test() {
+    use crate::{Scene, VectorField};
+
+    let mut scene = Scene::parse(include_str!("../scenes/default.toml")).unwrap();
+    scene.grid.width = [-2.0, 3.0];
+    scene.grid.height = [-2.0, 3.0];
+    let world = scene.world();
+
+    let model = WarpingModel {
+        directions: 36,
+        ..WarpingModel::default()
+    };
+    let bee = scene.bee(&world).unwrap().with_model(Box::new(model));
+    let error = VectorField::generate(bee, &world)
+        .avg_angular_error
+        .unwrap();
+
+    println!("{}", error.to_degrees());
+
+    // within a few units of home the field leads home
+    assert!(error < 20f32.to_radians());
+}
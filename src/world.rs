@@ -0,0 +1,112 @@
+use std::ops::Range;
+
+use crate::{Obstacle, Vec2};
+
+/// Grid struct for all your grid needs
+/// the grid positions start at the start of both ranges and are spacing apart
+#[derive(Clone, PartialEq, Debug)]
+pub struct Grid {
+    /// width of the grid, the end is excluded
+    pub width: Range<f32>,
+    /// height of the grid, the end is excluded
+    pub height: Range<f32>,
+    /// distance between two neighbouring grid positions
+    pub spacing: f32,
+}
+
+/// World that holds obstacles and the grid the bee is allowed to be on
+pub struct World {
+    /// list of obstacles in the world
+    /// NOTE: the obstacles do not have to be on the grid
+    pub obstacles: Vec<Box<dyn Obstacle>>,
+    /// the grid that allows the bee to move
+    pub grid: Grid,
+}
+
+impl Grid {
+    /// creates a new grid spanning the given ranges
+    pub fn new(width: Range<f32>, height: Range<f32>, spacing: f32) -> Grid {
+        Grid {
+            width,
+            height,
+            spacing,
+        }
+    }
+    /// number of grid positions along the x-axis
+    pub fn columns(&self) -> usize {
+        Grid::count(&self.width, self.spacing)
+    }
+    /// number of grid positions along the y-axis
+    pub fn rows(&self) -> usize {
+        Grid::count(&self.height, self.spacing)
+    }
+    /// position of the grid point in the given column and row
+    pub fn position(&self, column: usize, row: usize) -> Vec2<f32> {
+        Vec2::<f32>::new(
+            self.width.start + column as f32 * self.spacing,
+            self.height.start + row as f32 * self.spacing,
+        )
+    }
+    /// iterates over every position of the grid
+    /// row by row, starting at the bottom left
+    pub fn positions(&self) -> impl Iterator<Item = Vec2<f32>> + '_ {
+        (0..self.rows())
+            .flat_map(move |row| (0..self.columns()).map(move |column| self.position(column, row)))
+    }
+    /// turns a position on the grid into the indices used by VectorField::vectors
+    /// the first index is the column, the second the row counted from the top
+    /// positions between grid points are rounded to the closest one
+    pub fn index(&self, position: Vec2<f32>) -> Vec2<usize> {
+        let x = ((position[0] - self.width.start) / self.spacing).round() as usize;
+        let y = ((position[1] - self.height.start) / self.spacing).round() as usize;
+        let y = self.rows() - y - 1; // has to be reversed
+
+        Vec2::<usize>::new(x, y)
+    }
+    /// whether the position lies in the area of the grid
+    /// every grid position covers half the spacing on each side
+    pub fn contains(&self, position: Vec2<f32>) -> bool {
+        if self.columns() == 0 || self.rows() == 0 {
+            return false;
+        }
+        let first = self.position(0, 0);
+        let last = self.position(self.columns() - 1, self.rows() - 1);
+        let margin = self.spacing / 2.0;
+        (first[0] - margin..=last[0] + margin).contains(&position[0])
+            && (first[1] - margin..=last[1] + margin).contains(&position[1])
+    }
+    /// counts the grid positions in the range
+    fn count(range: &Range<f32>, spacing: f32) -> usize {
+        // a grid without a positive spacing has no positions
+        if spacing.is_nan() || spacing <= 0.0 {
+            return 0;
+        }
+        // the small tolerance keeps floating point errors from adding a position at the end
+        ((range.end - range.start) / spacing - 1e-4).ceil().max(0.0) as usize
+    }
+}
+
+impl World {
+    /// creates a new world from a list of obstacles and a grid
+    pub fn new(obstacles: Vec<Box<dyn Obstacle>>, grid: Grid) -> World {
+        World { obstacles, grid }
+    }
+}
+
+#[test]
+fn grid_positions_test() {
+    let grid = Grid::new(-7.0..8.0, -7.0..8.0, 1.0);
+
+    assert_eq!(grid.columns(), 15);
+    assert_eq!(grid.rows(), 15);
+
+    let fine = Grid::new(-1.0..1.0, 0.0..0.5, 0.1);
+
+    assert_eq!(fine.columns(), 20);
+    assert_eq!(fine.rows(), 5);
+
+    let last = fine.positions().last().unwrap();
+
+    assert!((last[0] - 0.9).abs() < 1e-4);
+    assert!((last[1] - 0.4).abs() < 1e-4);
+}